@@ -0,0 +1,111 @@
+// Benchmarks of the board primitives: shifts, move generation, conversions, flips and perft.
+//
+// Run with `cargo bench --bench board`. Perft groups report leaf nodes per second.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use othlib::board::bitboard::BitBoard;
+use othlib::board::direction::DIRECTIONS;
+use othlib::board::movegen;
+use othlib::board::notation::parse_moves;
+use othlib::board::perft::{perft, perft_with};
+use othlib::board::position::Position;
+
+// a middle game position, with moves in most directions
+fn middle_game() -> Position {
+    let mut position = Position::new();
+    for mv in parse_moves("f5d6c3d3c4f4f6g5e3f3g4e2d2h3e6g3c6f7g6c5").unwrap() {
+        position.play(mv).unwrap();
+    }
+    position
+}
+
+fn bench_shr(c: &mut Criterion) {
+    let board = middle_game().player();
+
+    c.bench_function("shr/8 directions", |b| {
+        b.iter(|| {
+            DIRECTIONS
+                .iter()
+                .fold(0u64, |acc, dir| acc ^ black_box(board).shr(dir).bits)
+        })
+    });
+}
+
+fn bench_line_cap_moves(c: &mut Criterion) {
+    let position = middle_game();
+    let (player, opponent) = (position.player(), position.opponent());
+
+    c.bench_function("line_cap_moves", |b| {
+        b.iter(|| black_box(player).line_cap_moves(&black_box(opponent)))
+    });
+}
+
+fn bench_conversions(c: &mut Criterion) {
+    let board = middle_game().black;
+
+    c.bench_function("conversions/Vec<usize>", |b| {
+        b.iter(|| Vec::<usize>::from(&black_box(board)))
+    });
+    c.bench_function("conversions/Vec<String>", |b| {
+        b.iter(|| Vec::<String>::from(&black_box(board)))
+    });
+    c.bench_function("conversions/from Vec<&str>", |b| {
+        b.iter(|| BitBoard::from(black_box(vec!["A1", "D4", "E5", "F5", "H8"])))
+    });
+}
+
+fn bench_flips(c: &mut Criterion) {
+    let position = middle_game();
+    let (player, opponent) = (position.player(), position.opponent());
+
+    let mut squares = Vec::new();
+    let mut moves = position.legal_moves().bits;
+    while moves != 0 {
+        squares.push(moves.trailing_zeros() as usize);
+        moves &= moves - 1;
+    }
+
+    let mut group = c.benchmark_group("flips");
+    group.throughput(Throughput::Elements(squares.len() as u64));
+    for backend in movegen::all() {
+        group.bench_function(backend.name(), |b| {
+            b.iter(|| {
+                squares.iter().fold(0u64, |acc, &square| {
+                    acc ^ backend
+                        .flips(&black_box(player), &black_box(opponent), square)
+                        .bits
+                })
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_perft(c: &mut Criterion) {
+    let start = Position::new();
+
+    let mut group = c.benchmark_group("perft");
+    group.sample_size(10);
+    for depth in &[6, 8] {
+        group.throughput(Throughput::Elements(perft(&start, *depth).nodes));
+        for backend in movegen::all() {
+            group.bench_with_input(
+                BenchmarkId::new(backend.name(), depth),
+                depth,
+                |b, &depth| b.iter(|| perft_with(backend.as_ref(), &start, depth)),
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    bench_shr,
+    bench_line_cap_moves,
+    bench_conversions,
+    bench_flips,
+    bench_perft
+);
+criterion_main!(benches);
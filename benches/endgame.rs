@@ -0,0 +1,38 @@
+// Benchmarks of the endgame solver on the FFO test positions shipped in data/ffo.obf, the ones
+// checked by the ffo runner. Results are in nodes per second.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+
+use othlib::board::position::Position;
+use othlib::engine::endgame::EndgameSolver;
+use othlib::game::obf::{ObfPosition, FFO_FIRST, FFO_OBF};
+
+fn positions() -> Vec<(usize, Position)> {
+    ObfPosition::all_from_obf(FFO_OBF)
+        .unwrap()
+        .into_iter()
+        .enumerate()
+        .map(|(i, obf)| (FFO_FIRST + i, obf.position))
+        .collect()
+}
+
+fn bench_ffo(c: &mut Criterion) {
+    let mut solver = EndgameSolver::new();
+
+    let mut group = c.benchmark_group("ffo");
+    group.sample_size(10);
+    for (id, position) in positions() {
+        solver.clear();
+        group.throughput(Throughput::Elements(solver.solve(&position).nodes));
+        group.bench_with_input(BenchmarkId::from_parameter(id), &position, |b, position| {
+            b.iter(|| {
+                solver.clear();
+                solver.solve(position)
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_ffo);
+criterion_main!(benches);
@@ -0,0 +1,20 @@
+// With the `capi` feature, write the C header of the API to include/othlib.h
+
+fn main() {
+    #[cfg(feature = "capi")]
+    {
+        println!("cargo:rerun-if-changed=src/capi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        // only the API module is parsed, other types being opaque
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir))
+            .expect("unable to read cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{}/src/capi.rs", crate_dir))
+            .generate()
+            .expect("unable to generate the C header")
+            .write_to_file(format!("{}/include/othlib.h", crate_dir));
+    }
+}
@@ -0,0 +1,358 @@
+#ifndef OTHLIB_H
+#define OTHLIB_H
+
+/* Generated by cbindgen from src/capi.rs: don't edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * Version of the API, increased on incompatible changes
+ */
+#define OTH_API_VERSION 1
+
+/**
+ * A pass, wherever a square is expected
+ */
+#define OTH_PASS -1
+
+/**
+ * Length of the longest principal variation
+ */
+#define OTH_MAX_PV 60
+
+/**
+ * Result of a function which can fail
+ */
+typedef enum {
+  OTH_STATUS_OK = 0,
+  /**
+   * a pointer argument is null
+   */
+  OTH_STATUS_NULL_POINTER = -1,
+  /**
+   * an argument is out of range or can't be parsed
+   */
+  OTH_STATUS_INVALID_ARGUMENT = -2,
+  /**
+   * the move is not legal in the position
+   */
+  OTH_STATUS_ILLEGAL_MOVE = -3,
+  /**
+   * a file can't be read, or has a wrong format
+   */
+  OTH_STATUS_FILE_ERROR = -4,
+} OthStatus;
+
+/**
+ * Color of the discs
+ */
+typedef enum {
+  OTH_COLOR_BLACK = 0,
+  OTH_COLOR_WHITE = 1,
+} OthColor;
+
+/**
+ * Opaque engine: evaluation, search and transposition tables
+ */
+typedef struct OthEngine OthEngine;
+
+/**
+ * Opaque position: discs and color to move
+ */
+typedef struct OthPosition OthPosition;
+
+/**
+ * Opaque content of a WTHOR game file
+ */
+typedef struct OthWThorFile OthWThorFile;
+
+/**
+ * Limits of a search. A limit is unused when 0, and a search without any limit solves the
+ * position exactly.
+ */
+typedef struct {
+  uint32_t depth;
+  uint64_t nodes;
+  uint64_t time_ms;
+} OthLimits;
+
+/**
+ * Outcome of a search
+ */
+typedef struct {
+  /**
+   * square of the best move, or OTH_PASS
+   */
+  int32_t best_move;
+  /**
+   * disc differential for the color to move
+   */
+  float score;
+  /**
+   * true when the score is proven
+   */
+  bool exact;
+  uint32_t depth;
+  uint64_t nodes;
+  uint64_t time_ms;
+  /**
+   * expected moves from the position, the best one first
+   */
+  int32_t pv[OTH_MAX_PV];
+  uint32_t pv_length;
+} OthSearchResult;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Version of the API, to check the header matches the library
+ */
+uint32_t oth_api_version(void);
+
+/**
+ * Legal moves of `player` against `opponent`
+ */
+uint64_t oth_moves(uint64_t player, uint64_t opponent);
+
+/**
+ * Opponent discs flipped by `player` playing on `square`, 0 if the square is invalid
+ */
+uint64_t oth_flips(uint64_t player, uint64_t opponent, int32_t square);
+
+/**
+ * Parse a move in algebric notation (`f5`, `F5`, `pa` or `pass`) into a square
+ *
+ * # Safety
+ * `text` must be null or a NUL-terminated string, and `square` must be null or valid for writes.
+ */
+OthStatus oth_parse_move(const char *text, int32_t *square);
+
+/**
+ * Write a square in algebric notation (e.g. `F5`, or `PA` for a pass) to a buffer of at least 3
+ * bytes, NUL included
+ *
+ * # Safety
+ * `buffer` must be null or valid for `size` bytes of writes.
+ */
+OthStatus oth_move_to_string(int32_t square, char *buffer, size_t size);
+
+/**
+ * The starting position
+ */
+OthPosition *oth_position_new(void);
+
+/**
+ * A position from its 64 squares from A1 to H8: `X` or `*` for black, `O` for white, `-` or `.`
+ * for empty squares, blanks being ignored. Returns null if the squares are invalid.
+ *
+ * # Safety
+ * `squares` must be null or a NUL-terminated string.
+ */
+OthPosition *oth_position_from_squares(const char *squares, OthColor side);
+
+/**
+ * A copy of a position
+ *
+ * # Safety
+ * `position` must be null or a live handle.
+ */
+OthPosition *oth_position_clone(const OthPosition *position);
+
+/**
+ * Release a position
+ *
+ * # Safety
+ * `position` must be null or a live handle, which can't be used after.
+ */
+void oth_position_free(OthPosition *position);
+
+/**
+ * Black discs
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+uint64_t oth_position_black(const OthPosition *position);
+
+/**
+ * White discs
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+uint64_t oth_position_white(const OthPosition *position);
+
+/**
+ * Color to move
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+OthColor oth_position_side(const OthPosition *position);
+
+/**
+ * Legal moves of the color to move
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+uint64_t oth_position_legal_moves(const OthPosition *position);
+
+/**
+ * Play a square, or OTH_PASS when the color to move has no legal move. The flipped discs are
+ * written to `flipped` unless it's null.
+ *
+ * # Safety
+ * `position` must be null or a live handle, and `flipped` null or valid for writes.
+ */
+OthStatus oth_position_play(OthPosition *position, int32_t square, uint64_t *flipped);
+
+/**
+ * True if the color to move has no legal move
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+bool oth_position_must_pass(const OthPosition *position);
+
+/**
+ * True if no color can play
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+bool oth_position_is_game_over(const OthPosition *position);
+
+/**
+ * Number of empty squares
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+uint32_t oth_position_empties(const OthPosition *position);
+
+/**
+ * Final disc differential for black, empty squares going to the winner
+ *
+ * # Safety
+ * `position` must be a live handle.
+ */
+int32_t oth_position_final_score(const OthPosition *position);
+
+/**
+ * An engine evaluating positions with the pattern weights of a weight file, or with the
+ * mobility evaluation if `weights` is null. Returns null if the weights can't be read.
+ *
+ * # Safety
+ * `weights` must be null or a NUL-terminated string.
+ */
+OthEngine *oth_engine_new(const char *weights);
+
+/**
+ * Release an engine
+ *
+ * # Safety
+ * `engine` must be null or a live handle, which can't be used after.
+ */
+void oth_engine_free(OthEngine *engine);
+
+/**
+ * Number of threads of the midgame search and of the endgame solver
+ *
+ * # Safety
+ * `engine` must be null or a live handle.
+ */
+OthStatus oth_engine_set_threads(OthEngine *engine, uint32_t threads);
+
+/**
+ * Make the midgame search selective with the Multi-ProbCut parameters of a file written by the
+ * probcut utility, or turn selectivity off if `path` is null
+ *
+ * # Safety
+ * `engine` must be null or a live handle, and `path` null or a NUL-terminated string.
+ */
+OthStatus oth_engine_set_probcut(OthEngine *engine, const char *path);
+
+/**
+ * Forget previous searches
+ *
+ * # Safety
+ * `engine` must be null or a live handle.
+ */
+void oth_engine_clear(OthEngine *engine);
+
+/**
+ * Search a position within limits, or solve it if `limits` is null or has no limit
+ *
+ * # Safety
+ * `engine` and `position` must be null or live handles, `limits` null or valid for reads and
+ * `result` null or valid for writes.
+ */
+OthStatus oth_engine_search(OthEngine *engine,
+                            const OthPosition *position,
+                            const OthLimits *limits,
+                            OthSearchResult *result);
+
+/**
+ * Read the games of a WTHOR game file. Returns null if the file can't be read, is truncated
+ * or a game is invalid.
+ *
+ * # Safety
+ * `path` must be null or a NUL-terminated string.
+ */
+OthWThorFile *oth_wthor_load(const char *path);
+
+/**
+ * Release the games of a WTHOR file
+ *
+ * # Safety
+ * `file` must be null or a live handle, which can't be used after.
+ */
+void oth_wthor_free(OthWThorFile *file);
+
+/**
+ * Number of games
+ *
+ * # Safety
+ * `file` must be a live handle.
+ */
+size_t oth_wthor_count(const OthWThorFile *file);
+
+/**
+ * Write the moves of a game to `moves`, passes included, and return their number. At most
+ * `size` moves are written, the result being the full number of moves of the game, or -1 if
+ * `game` is out of range.
+ *
+ * # Safety
+ * `file` must be a live handle, and `moves` null or valid for `size` writes.
+ */
+int32_t oth_wthor_moves(const OthWThorFile *file, size_t game, int32_t *moves, size_t size);
+
+/**
+ * Final disc differential for black of a game
+ *
+ * # Safety
+ * `file` must be a live handle, and `score` null or valid for writes.
+ */
+OthStatus oth_wthor_score(const OthWThorFile *file, size_t game, int32_t *score);
+
+/**
+ * The position of a game before the move at `ply`, 0 being the starting position. Returns null
+ * if `game` or `ply` is out of range.
+ *
+ * # Safety
+ * `file` must be a live handle.
+ */
+OthPosition *oth_wthor_position(const OthWThorFile *file, size_t game, size_t ply);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* OTHLIB_H */
@@ -1,6 +0,0 @@
-from othello.svg_doc import SVGDoc
-from othello.color import Color
-from othello.coordinate import Coordinate
-from othello.svg_board import SVGBoard
-from othello.bitboard import Bitboard
-
@@ -1,5 +0,0 @@
-from enum import Enum
-class Color(Enum):
-    black = 0
-    white = 1
-    red = 2
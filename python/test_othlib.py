@@ -0,0 +1,157 @@
+""" Unit tests of the othlib extension module. Build it with `maturin develop`, then run:
+
+    python -m unittest python/test_othlib.py
+"""
+import os
+import tempfile
+import unittest
+
+from othlib import (BitBoard, Color, Game, Position, SVGBoard, read_wthor_games,
+                    read_wthor_players)
+
+MAX_U64_VALUE = 2**64-1
+
+
+class TestBitBoard(unittest.TestCase):
+    def test_init(self):
+        self.assertEqual(BitBoard(1234).bits, 1234)
+
+        with self.assertRaises(OverflowError):
+            BitBoard(-1)
+        with self.assertRaises(OverflowError):
+            BitBoard(2**65)
+
+    def test_from_algebric_list(self):
+        value = BitBoard.from_algebric_list(["A1", "F8", "G8"])
+        self.assertEqual(value.bits, 2**63+6)
+        self.assertEqual(value.squares(), ["A1", "F8", "G8"])
+
+        with self.assertRaises(ValueError):
+            BitBoard.from_algebric_list(["A1", "F8", "Z8"])
+
+    def test_operators(self):
+        self.assertEqual(BitBoard(0), ~BitBoard.max_value())
+        self.assertNotEqual(BitBoard(0), BitBoard.max_value())
+        self.assertEqual((BitBoard(0) | BitBoard.max_value()).bits, MAX_U64_VALUE)
+        self.assertEqual((BitBoard(0b1100) & BitBoard(0b1010)).bits, 0b1000)
+        self.assertEqual((BitBoard(0b1100) ^ BitBoard(0b1010)).bits, 0b0110)
+
+    def test_shifts(self):
+        self.assertEqual((BitBoard.max_value() << 64).bits, 0)
+        self.assertEqual((BitBoard(2**63) << 1).bits, 0)
+        self.assertEqual((BitBoard(2**63) << -1).bits, 2**62)
+        self.assertEqual((BitBoard(1) >> 1).bits, 0)
+        self.assertEqual((BitBoard(1) >> -1).bits, 2)
+
+    def test_iter(self):
+        self.assertEqual(list(BitBoard(0)), [0]*64)
+        self.assertEqual(list(BitBoard.max_value()), [1]*64)
+
+    def test_row_col(self):
+        value = BitBoard(0b00000000_11111111_11111111_11111111_11111111_11111111_11111111_00000000)
+        self.assertEqual(value.row(0), [0]*8)
+        self.assertEqual(value.row(1), [1]*8)
+        self.assertEqual(value.col(0), [0]+[1]*6+[0])
+
+        with self.assertRaises(ValueError):
+            value.row(8)
+        with self.assertRaises(ValueError):
+            value.col(8)
+
+    def test_ascii(self):
+        value = BitBoard(0b00000000_11111111_11111111_11111111_11111111_11111111_11111111_00000000)
+        self.assertEqual(value.to_ascii(), "00000000\n" + "11111111\n"*6 + "00000000")
+
+    def test_special_shift(self):
+        shifted = BitBoard.max_value() @ 'N'
+        for i in range(0, 7):
+            self.assertEqual(shifted.row(i), [1]*8)
+        self.assertEqual(shifted.row(7), [0]*8)
+
+        shifted = BitBoard.max_value() @ 'E'
+        self.assertEqual(shifted.col(0), [0]*8)
+
+        with self.assertRaises(ValueError):
+            BitBoard.max_value() @ 'X'
+
+    def test_possible_moves(self):
+        black = BitBoard(0b01000000_11011110_01000110_00101110_00011010_00011100_00000000_00000000)
+        white = BitBoard(0b00111110_00100000_00111000_00010000_00100000_00000000_00000000_00000000)
+        self.assertEqual(white.possible_moves(black).bits, 9223796178917988864)
+
+
+class TestPosition(unittest.TestCase):
+    def test_play(self):
+        position = Position()
+        self.assertEqual(position.side, Color.black)
+        self.assertEqual(position.legal_move_list(), ["D3", "C4", "F5", "E6"])
+
+        flipped = position.play("f5")
+        self.assertEqual(flipped.squares(), ["E5"])
+        self.assertEqual(position.side, Color.white)
+        self.assertEqual(position.count(Color.black), 4)
+
+        with self.assertRaises(ValueError):
+            position.play("a1")
+
+    def test_from_squares(self):
+        squares = "-------- -------- -------- ---OX--- ---XO--- -------- -------- --------"
+        self.assertEqual(Position.from_squares(squares, Color.black), Position())
+
+        with self.assertRaises(ValueError):
+            Position.from_squares("XO", Color.black)
+
+
+class TestGame(unittest.TestCase):
+    def test_moves(self):
+        game = Game.from_moves("f5d6c3d3c4")
+        self.assertEqual(len(game), 5)
+        self.assertEqual(str(game), "f5d6c3d3c4")
+        self.assertEqual(game.position_at(1).to_squares()[24:40], "---OX------XXX--")
+
+        self.assertEqual(game.undo(), "C4")
+        game.play("c4")
+        self.assertEqual(game.final_position().nb_empty(), 55)
+
+        with self.assertRaises(ValueError):
+            Game.from_moves("f5f5")
+
+    def test_ggf(self):
+        game = Game.from_moves("f5d6")
+        self.assertEqual(Game.from_ggf(game.to_ggf()).moves, ["F5", "D6"])
+
+    def test_wthor(self):
+        with self.assertRaises(OSError):
+            read_wthor_games("no_such_file.wtb")
+
+        # a header announcing 2 records, and less than one game or two players
+        with tempfile.NamedTemporaryFile(suffix=".wtb", delete=False) as wtb:
+            wtb.write(bytes([0, 0, 0, 0, 2, 0, 0, 0, 2, 0, 0, 0, 8, 0, 0, 0]) + bytes(30))
+        try:
+            with self.assertRaises(ValueError):
+                read_wthor_games(wtb.name)
+            with self.assertRaises(ValueError):
+                read_wthor_players(wtb.name)
+        finally:
+            os.remove(wtb.name)
+
+
+class TestSVGBoard(unittest.TestCase):
+    def test_position(self):
+        board = SVGBoard()
+        board.draw_position(Position())
+        board.draw_piece_from_algebric("A1", Color.white)
+
+        self.assertTrue(board.tags.endswith("</svg>"))
+        self.assertEqual(board.tags.count("<circle"), 5)
+
+    def test_shading(self):
+        board = SVGBoard(250)
+        board.shade_squares(Position().legal_moves(), "red", 0.25)
+
+        self.assertIn('width="250"', board.tags)
+        self.assertEqual(board.tags.count("fill:red"), 4)
+
+
+if __name__ == '__main__':
+    unittest.main()
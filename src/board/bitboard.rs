@@ -1,9 +1,10 @@
 use std::convert::From;
-use std::string::ToString;
+use std::fmt;
 
 use crate::board::coordinate::{Coordinate, BOARD_SIZE};
 use crate::board::direction::{Direction, DIRECTIONS};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub struct BitBoard {
     pub bits: u64,
 }
@@ -11,7 +12,7 @@ pub struct BitBoard {
 impl BitBoard {
     /// Just a wraopper around an `u64` value
     pub fn new(bits: u64) -> BitBoard {
-        BitBoard { bits: bits }
+        BitBoard { bits }
     }
 
     /// useful static methods
@@ -19,7 +20,7 @@ impl BitBoard {
         BitBoard::new(0)
     }
     pub fn max_value() -> BitBoard {
-        BitBoard::new(u64::max_value())
+        BitBoard::new(u64::MAX)
     }
 
     /// Return i-th row of a bitboard
@@ -44,7 +45,7 @@ impl BitBoard {
     /// ```
     pub fn row(&self, i: usize) -> Vec<usize> {
         if i > 7 {
-            panic!(format!("Row number {} is wrong !", i));
+            panic!("Row number {} is wrong !", i);
         }
         let bits = Vec::<usize>::from(self);
 
@@ -72,7 +73,7 @@ impl BitBoard {
     /// ```
     pub fn col(&self, j: usize) -> Vec<usize> {
         if j > 7 {
-            panic!(format!("Col number {} is wrong !", j));
+            panic!("Col number {} is wrong !", j);
         }
         let bits = Vec::<usize>::from(self);
         let col: Vec<_> = bits
@@ -85,6 +86,141 @@ impl BitBoard {
         col
     }
 
+    /// Return the i-th row as a byte, the A column being the most significant bit
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "C1", "H2"]);
+    /// assert_eq!(bitboard.row_bits(0), 0b1010_0000);
+    /// assert_eq!(bitboard.row_bits(1), 0b0000_0001);
+    /// ```
+    pub fn row_bits(&self, i: usize) -> u8 {
+        (self.bits >> (8 * (7 - i))) as u8
+    }
+
+    /// Return the j-th column as a byte, the 1st row being the most significant bit
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "A3", "B8"]);
+    /// assert_eq!(bitboard.col_bits(0), 0b1010_0000);
+    /// assert_eq!(bitboard.col_bits(1), 0b0000_0001);
+    /// ```
+    pub fn col_bits(&self, j: usize) -> u8 {
+        self.transpose().row_bits(j)
+    }
+
+    /// Return the squares of a diagonal parallel to A1-H8, from top left to bottom right, the
+    /// first square being the most significant bit. Diagonal `k` starts on the first row at column
+    /// `k` when `k >= 0`, and on the first column at row `-k` otherwise, so has `8 - |k|` squares.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "H8", "B1", "A2", "C3"]);
+    /// assert_eq!(bitboard.diagonal_bits(0), 0b1010_0001);
+    /// assert_eq!(bitboard.diagonal_bits(1), 0b100_0000);
+    /// assert_eq!(bitboard.diagonal_bits(-1), 0b100_0000);
+    /// ```
+    pub fn diagonal_bits(&self, k: isize) -> u8 {
+        let (x0, y0) = if k >= 0 {
+            (k as usize, 0)
+        } else {
+            (0, (-k) as usize)
+        };
+        let len = BOARD_SIZE - k.unsigned_abs();
+
+        (0..len).fold(0u8, |acc, t| {
+            let square = 63 - Coordinate::to_linear((x0 + t, y0 + t));
+            (acc << 1) | ((self.bits >> square) & 1) as u8
+        })
+    }
+
+    /// Mirror the board top to bottom: A1 goes to A8
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "C2"]);
+    /// assert_eq!(bitboard.flip_vertical(), BitBoard::from(vec!["A8", "C7"]));
+    /// ```
+    pub fn flip_vertical(&self) -> BitBoard {
+        BitBoard::new(self.bits.swap_bytes())
+    }
+
+    /// Mirror the board left to right: A1 goes to H1
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "C2"]);
+    /// assert_eq!(bitboard.flip_horizontal(), BitBoard::from(vec!["H1", "F2"]));
+    /// ```
+    pub fn flip_horizontal(&self) -> BitBoard {
+        let mut x = self.bits;
+        x = ((x >> 1) & 0x5555_5555_5555_5555) | ((x & 0x5555_5555_5555_5555) << 1);
+        x = ((x >> 2) & 0x3333_3333_3333_3333) | ((x & 0x3333_3333_3333_3333) << 2);
+        x = ((x >> 4) & 0x0F0F_0F0F_0F0F_0F0F) | ((x & 0x0F0F_0F0F_0F0F_0F0F) << 4);
+        BitBoard::new(x)
+    }
+
+    /// Mirror the board along the A1-H8 diagonal: rows become columns, C1 goes to A3
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let bitboard = BitBoard::from(vec!["A1", "C1", "H2"]);
+    /// assert_eq!(bitboard.transpose(), BitBoard::from(vec!["A1", "A3", "B8"]));
+    /// ```
+    pub fn transpose(&self) -> BitBoard {
+        let mut x = self.bits;
+        let mut t = 0x0F0F_0F0F_0000_0000 & (x ^ (x << 28));
+        x ^= t ^ (t >> 28);
+        t = 0x3333_0000_3333_0000 & (x ^ (x << 14));
+        x ^= t ^ (t >> 14);
+        t = 0x5500_5500_5500_5500 & (x ^ (x << 7));
+        x ^= t ^ (t >> 7);
+        BitBoard::new(x)
+    }
+
+    /// The 8 images of the board by the symmetries of the square, the board itself first
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let corners: Vec<_> = BitBoard::from(vec!["B1"])
+    ///     .symmetries()
+    ///     .iter()
+    ///     .map(|b| Vec::<String>::from(b)[0].clone())
+    ///     .collect();
+    /// assert_eq!(corners, ["B1", "G1", "B8", "G8", "A2", "A7", "H2", "H7"]);
+    /// ```
+    pub fn symmetries(&self) -> [BitBoard; 8] {
+        let horizontal = self.flip_horizontal();
+        let vertical = self.flip_vertical();
+        let both = horizontal.flip_vertical();
+
+        [
+            *self,
+            horizontal,
+            vertical,
+            both,
+            self.transpose(),
+            horizontal.transpose(),
+            vertical.transpose(),
+            both.transpose(),
+        ]
+    }
+
     /// Shift towards direction
     ///
     /// # Examples
@@ -172,11 +308,11 @@ impl BitBoard {
         match dir {
             Direction::E => BitBoard::new((self.bits >> 1) & 0x7F7F7F7F7F7F7F7Fu64),
             Direction::SE => BitBoard::new((self.bits >> 9) & 0x007F7F7F7F7F7F7Fu64),
-            Direction::S => BitBoard::new((self.bits >> 8) & 0xFFFFFFFFFFFFFFFFu64),
+            Direction::S => BitBoard::new(self.bits >> 8),
             Direction::SW => BitBoard::new((self.bits >> 7) & 0x00FEFEFEFEFEFEFEu64),
             Direction::W => BitBoard::new((self.bits << 1) & 0xFEFEFEFEFEFEFEFEu64),
             Direction::NW => BitBoard::new((self.bits << 9) & 0xFEFEFEFEFEFEFE00u64),
-            Direction::N => BitBoard::new((self.bits << 8) & 0xFFFFFFFFFFFFFFFFu64),
+            Direction::N => BitBoard::new(self.bits << 8),
             Direction::NE => BitBoard::new((self.bits << 7) & 0x7F7F7F7F7F7F7F00u64),
         }
     }
@@ -198,8 +334,8 @@ impl BitBoard {
     ///
     /// moves = white.line_cap_moves(&black);
     /// assert_eq!(moves.bits, 0);
-    /// 
-    /// 
+    ///
+    ///
     /// ```
     pub fn line_cap_moves(&self, opponent_player: &BitBoard) -> BitBoard {
         let mut possible_moves = 0u64;
@@ -221,6 +357,78 @@ impl BitBoard {
 
         BitBoard::new(possible_moves)
     }
+
+    /// Return the possible moves by shifting 6 times in each direction, without loop on the
+    /// pieces found: a line can't be made of more than 6 opponent pieces
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let black = BitBoard::new(0b01000000_11011110_01000110_00101110_00011010_00011100_00000000_00000000);
+    /// let white = BitBoard::new(0b00111110_00100000_00111000_00010000_00100000_00000000_00000000_00000000);
+    ///
+    /// assert_eq!(white.generate_moves(&black), white.line_cap_moves(&black));
+    /// assert_eq!(black.generate_moves(&white), black.line_cap_moves(&white));
+    /// ```
+    pub fn generate_moves(&self, opponent_player: &BitBoard) -> BitBoard {
+        let empty = !(self.bits | opponent_player.bits);
+        let mut legal_moves = 0u64;
+
+        for dir in &DIRECTIONS {
+            let mut x = self.shr(dir).bits & opponent_player.bits;
+
+            // add opponent pieces adjacent to those, and so on
+            x |= BitBoard::new(x).shr(dir).bits & opponent_player.bits;
+            x |= BitBoard::new(x).shr(dir).bits & opponent_player.bits;
+            x |= BitBoard::new(x).shr(dir).bits & opponent_player.bits;
+            x |= BitBoard::new(x).shr(dir).bits & opponent_player.bits;
+            x |= BitBoard::new(x).shr(dir).bits & opponent_player.bits;
+
+            // empty squares adjacent to those are valid moves
+            legal_moves |= BitBoard::new(x).shr(dir).bits & empty;
+        }
+
+        BitBoard::new(legal_moves)
+    }
+
+    /// Return the opponent pieces flipped when playing on the `square` bit index (63 is A1)
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let black = BitBoard::from(vec!["D5", "E4"]);
+    /// let white = BitBoard::from(vec!["D4", "E5"]);
+    ///
+    /// // F5 is bit 26 and only flips E5
+    /// let flipped = black.flips(&white, 26);
+    /// assert_eq!(flipped, BitBoard::from(vec!["E5"]));
+    ///
+    /// // nothing to flip in the corner
+    /// assert_eq!(black.flips(&white, 63).bits, 0);
+    /// ```
+    pub fn flips(&self, opponent_player: &BitBoard, square: usize) -> BitBoard {
+        let mut flipped = 0u64;
+        let origin = BitBoard::new(1u64 << square);
+
+        // for each direction, follow opponent pieces and keep them if capped by one of ours
+        for dir in &DIRECTIONS {
+            let mut line = 0u64;
+            let mut cursor = origin.shr(dir);
+
+            while cursor.bits & opponent_player.bits != 0 {
+                line |= cursor.bits;
+                cursor = cursor.shr(dir);
+            }
+
+            if cursor.bits & self.bits != 0 {
+                flipped |= line;
+            }
+        }
+
+        BitBoard::new(flipped)
+    }
 }
 
 /// Convert a bitboard value to a string of 0 or 1
@@ -232,15 +440,9 @@ impl BitBoard {
 /// let bitboard = BitBoard::max_value();
 /// assert_eq!(bitboard.to_string(), "1".repeat(64));
 /// ```    
-impl ToString for BitBoard {
-    fn to_string(&self) -> String {
-        let mut bits = format!("{:#066b}", self.bits);
-
-        // 2 first chars are '0b'
-        bits.remove(0);
-        bits.remove(0);
-
-        bits
+impl fmt::Display for BitBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:064b}", self.bits)
     }
 }
 
@@ -324,3 +526,92 @@ impl From<Vec<&str>> for BitBoard {
         BitBoard::new(bits)
     }
 }
+
+/// Serialize a bitboard as a hex string like `"0x0000000810000000"` in text formats like JSON,
+/// where large integers lose precision, and as an `u64` in binary formats like MessagePack or
+/// bincode
+///
+/// # Examples
+/// ```
+/// use othlib::board::bitboard::BitBoard;
+///
+/// let bitboard = BitBoard::from(vec!["D5", "E4"]);
+/// assert_eq!(serde_json::to_string(&bitboard).unwrap(), r#""0x0000000810000000""#);
+///
+/// let msgpack = rmp_serde::to_vec(&bitboard).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<BitBoard>(&msgpack).unwrap(), bitboard);
+///
+/// let bytes = bincode::serialize(&bitboard).unwrap();
+/// assert_eq!(bytes.len(), 8);
+/// assert_eq!(bincode::deserialize::<BitBoard>(&bytes).unwrap(), bitboard);
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for BitBoard {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format!("0x{:016x}", self.bits))
+        } else {
+            serializer.serialize_u64(self.bits)
+        }
+    }
+}
+
+/// Deserialize a bitboard from a hex string, a list of algebric squares or an integer in text
+/// formats, and from an `u64` in binary formats
+///
+/// # Examples
+/// ```
+/// use othlib::board::bitboard::BitBoard;
+///
+/// let bitboard = BitBoard::from(vec!["D5", "E4"]);
+/// assert_eq!(serde_json::from_str::<BitBoard>(r#""0x0000000810000000""#).unwrap(), bitboard);
+/// assert_eq!(serde_json::from_str::<BitBoard>(r#""810000000""#).unwrap(), bitboard);
+/// assert_eq!(serde_json::from_str::<BitBoard>(r#"["d5", "E4"]"#).unwrap(), bitboard);
+/// assert_eq!(serde_json::from_str::<BitBoard>("34628173824").unwrap(), bitboard);
+///
+/// assert!(serde_json::from_str::<BitBoard>(r#""0xZZ""#).is_err());
+/// assert!(serde_json::from_str::<BitBoard>(r#"["D9"]"#).is_err());
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for BitBoard {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::{self, SeqAccess, Visitor};
+
+        use crate::board::notation::algebric_to_square;
+
+        struct BitBoardVisitor;
+
+        impl<'de> Visitor<'de> for BitBoardVisitor {
+            type Value = BitBoard;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a hex string, a list of algebric squares or an integer")
+            }
+
+            fn visit_u64<E: de::Error>(self, bits: u64) -> Result<BitBoard, E> {
+                Ok(BitBoard::new(bits))
+            }
+
+            fn visit_str<E: de::Error>(self, hex: &str) -> Result<BitBoard, E> {
+                let digits = hex.trim_start_matches("0x").trim_start_matches("0X");
+                u64::from_str_radix(digits, 16)
+                    .map(BitBoard::new)
+                    .map_err(|_| E::custom(format!("invalid hex bitboard '{}'", hex)))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<BitBoard, A::Error> {
+                let mut bits = 0u64;
+                while let Some(square) = seq.next_element::<String>()? {
+                    bits |= 1 << algebric_to_square(&square).map_err(de::Error::custom)?;
+                }
+                Ok(BitBoard::new(bits))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BitBoardVisitor)
+        } else {
+            deserializer.deserialize_u64(BitBoardVisitor)
+        }
+    }
+}
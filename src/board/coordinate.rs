@@ -19,7 +19,7 @@ impl Coordinate {
     /// ```      
     #[inline(always)]
     pub fn to_linear(pt: (usize, usize)) -> usize {
-        (pt.0 + BOARD_SIZE * pt.1) as usize
+        pt.0 + BOARD_SIZE * pt.1
     }
 
     /// Useful conversion function from linear index to coordinates
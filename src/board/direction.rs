@@ -12,6 +12,7 @@ pub const DIRECTIONS: [Direction; 8] = [
 
 /// All possible directions for moving pieces in a bitboard.
 #[derive(Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Direction {
     N,
     NE,
@@ -2,3 +2,7 @@ pub mod bitboard;
 pub mod color;
 pub mod coordinate;
 pub mod direction;
+pub mod movegen;
+pub mod notation;
+pub mod perft;
+pub mod position;
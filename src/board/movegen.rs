@@ -0,0 +1,368 @@
+// Move generation backends. They all compute legal moves and flipped pieces, but with different
+// algorithms so they can be benchmarked against each other:
+//
+// * LineCap: follows opponent pieces in each direction with a loop (BitBoard::line_cap_moves)
+// * Shift: shifts 6 times in each direction, without any loop (BitBoard::generate_moves)
+// * KoggeStone: branch-free parallel prefix fill, needing only 3 shifts per direction
+// * Avx2: Kogge-Stone fill with 4 directions computed at once in 256-bit registers (x86-64 only)
+
+use crate::board::bitboard::BitBoard;
+use crate::board::direction::{Direction, DIRECTIONS};
+
+/// A way to compute moves and flips
+pub trait MoveGenerator {
+    /// Backend name, used in reports
+    fn name(&self) -> &'static str;
+
+    /// Legal moves for `player`
+    fn moves(&self, player: &BitBoard, opponent: &BitBoard) -> BitBoard;
+
+    /// Opponent pieces flipped when `player` plays on `square` bit index
+    fn flips(&self, player: &BitBoard, opponent: &BitBoard, square: usize) -> BitBoard;
+}
+
+/// Line cap algorithm, used as a reference
+pub struct LineCap;
+
+impl MoveGenerator for LineCap {
+    fn name(&self) -> &'static str {
+        "linecap"
+    }
+
+    fn moves(&self, player: &BitBoard, opponent: &BitBoard) -> BitBoard {
+        player.line_cap_moves(opponent)
+    }
+
+    fn flips(&self, player: &BitBoard, opponent: &BitBoard, square: usize) -> BitBoard {
+        player.flips(opponent, square)
+    }
+}
+
+/// Unrolled shifts
+pub struct Shift;
+
+impl MoveGenerator for Shift {
+    fn name(&self) -> &'static str {
+        "shift"
+    }
+
+    fn moves(&self, player: &BitBoard, opponent: &BitBoard) -> BitBoard {
+        player.generate_moves(opponent)
+    }
+
+    fn flips(&self, player: &BitBoard, opponent: &BitBoard, square: usize) -> BitBoard {
+        player.flips(opponent, square)
+    }
+}
+
+// shift amount (positive to the left) and mask of the squares which can be reached, per direction
+#[inline(always)]
+fn shift_and_mask(dir: &Direction) -> (i32, u64) {
+    match dir {
+        Direction::E => (-1, 0x7F7F7F7F7F7F7F7Fu64),
+        Direction::SE => (-9, 0x7F7F7F7F7F7F7F7Fu64),
+        Direction::S => (-8, 0xFFFFFFFFFFFFFFFFu64),
+        Direction::SW => (-7, 0xFEFEFEFEFEFEFEFEu64),
+        Direction::W => (1, 0xFEFEFEFEFEFEFEFEu64),
+        Direction::NW => (9, 0xFEFEFEFEFEFEFEFEu64),
+        Direction::N => (8, 0xFFFFFFFFFFFFFFFFu64),
+        Direction::NE => (7, 0x7F7F7F7F7F7F7F7Fu64),
+    }
+}
+
+#[inline(always)]
+fn shift(bits: u64, n: i32) -> u64 {
+    if n > 0 {
+        bits << n
+    } else {
+        bits >> -n
+    }
+}
+
+// Kogge-Stone occluded fill: propagate `gen` through `pro` in the direction of `n`. `pro` must
+// already be masked so that pieces can't wrap around the board.
+#[inline(always)]
+fn kogge_stone_fill(mut gen: u64, mut pro: u64, n: i32) -> u64 {
+    gen |= pro & shift(gen, n);
+    pro &= shift(pro, n);
+    gen |= pro & shift(gen, 2 * n);
+    pro &= shift(pro, 2 * n);
+    gen |= pro & shift(gen, 4 * n);
+    gen
+}
+
+/// Branch-free Kogge-Stone parallel prefix algorithm
+pub struct KoggeStone;
+
+impl KoggeStone {
+    /// Same as `moves` on `u64` values
+    #[inline]
+    pub fn moves_bits(player: u64, opponent: u64) -> u64 {
+        let empty = !(player | opponent);
+        let mut moves = 0u64;
+
+        for dir in &DIRECTIONS {
+            let (n, mask) = shift_and_mask(dir);
+            let pro = opponent & mask;
+
+            // opponent pieces adjacent to ours, then the whole lines
+            let lines = kogge_stone_fill(pro & shift(player, n), pro, n);
+            moves |= shift(lines, n) & mask & empty;
+        }
+
+        moves
+    }
+
+    /// Same as `flips` on `u64` values
+    #[inline]
+    pub fn flips_bits(player: u64, opponent: u64, square: usize) -> u64 {
+        let origin = 1u64 << square;
+        let mut flipped = 0u64;
+
+        for dir in &DIRECTIONS {
+            let (n, mask) = shift_and_mask(dir);
+            let pro = opponent & mask;
+
+            // opponent line starting from the square, kept only if capped by one of ours
+            let line = kogge_stone_fill(pro & shift(origin, n), pro, n);
+            let capped = shift(line | origin, n) & mask & player;
+            flipped |= line & 0u64.wrapping_sub((capped != 0) as u64);
+        }
+
+        flipped
+    }
+}
+
+impl MoveGenerator for KoggeStone {
+    fn name(&self) -> &'static str {
+        "koggestone"
+    }
+
+    fn moves(&self, player: &BitBoard, opponent: &BitBoard) -> BitBoard {
+        BitBoard::new(KoggeStone::moves_bits(player.bits, opponent.bits))
+    }
+
+    fn flips(&self, player: &BitBoard, opponent: &BitBoard, square: usize) -> BitBoard {
+        BitBoard::new(KoggeStone::flips_bits(player.bits, opponent.bits, square))
+    }
+}
+
+/// Kogge-Stone with AVX2 instructions. Only available when the CPU supports it.
+pub struct Avx2 {
+    _private: (),
+}
+
+impl Avx2 {
+    /// Return the backend if the CPU supports AVX2
+    pub fn new() -> Option<Avx2> {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                return Some(Avx2 { _private: () });
+            }
+        }
+        None
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod avx2 {
+    use std::arch::x86_64::*;
+
+    // lanes are W, N, NW, NE for left shifts, and E, S, SE, SW for right shifts
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn shifts() -> (__m256i, __m256i, __m256i, __m256i) {
+        let n1 = _mm256_set_epi64x(7, 9, 8, 1);
+        let n2 = _mm256_set_epi64x(14, 18, 16, 2);
+        let n4 = _mm256_set_epi64x(28, 36, 32, 4);
+        let masks = _mm256_set_epi64x(
+            0x7F7F7F7F7F7F7F7Fu64 as i64,
+            0xFEFEFEFEFEFEFEFEu64 as i64,
+            -1,
+            0xFEFEFEFEFEFEFEFEu64 as i64,
+        );
+        (n1, n2, n4, masks)
+    }
+
+    // or the 4 lanes together
+    #[inline]
+    #[target_feature(enable = "avx2")]
+    unsafe fn reduce(v: __m256i) -> u64 {
+        let x = _mm_or_si128(_mm256_castsi256_si128(v), _mm256_extracti128_si256(v, 1));
+        let x = _mm_or_si128(x, _mm_unpackhi_epi64(x, x));
+        _mm_cvtsi128_si64(x) as u64
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn moves(player: u64, opponent: u64) -> u64 {
+        let (n1, n2, n4, left_masks) = shifts();
+        // right shifts mirror left ones: W<->E, N<->S, NW<->SE, NE<->SW
+        let right_masks = _mm256_set_epi64x(
+            0xFEFEFEFEFEFEFEFEu64 as i64,
+            0x7F7F7F7F7F7F7F7Fu64 as i64,
+            -1,
+            0x7F7F7F7F7F7F7F7Fu64 as i64,
+        );
+
+        let p = _mm256_set1_epi64x(player as i64);
+        let o = _mm256_set1_epi64x(opponent as i64);
+
+        // left shifts
+        let pro = _mm256_and_si256(o, left_masks);
+        let mut gen = _mm256_and_si256(pro, _mm256_sllv_epi64(p, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro, _mm256_sllv_epi64(gen, n1)));
+        let pro2 = _mm256_and_si256(pro, _mm256_sllv_epi64(pro, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro2, _mm256_sllv_epi64(gen, n2)));
+        let pro4 = _mm256_and_si256(pro2, _mm256_sllv_epi64(pro2, n2));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro4, _mm256_sllv_epi64(gen, n4)));
+        let left = _mm256_and_si256(_mm256_sllv_epi64(gen, n1), left_masks);
+
+        // right shifts
+        let pro = _mm256_and_si256(o, right_masks);
+        let mut gen = _mm256_and_si256(pro, _mm256_srlv_epi64(p, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro, _mm256_srlv_epi64(gen, n1)));
+        let pro2 = _mm256_and_si256(pro, _mm256_srlv_epi64(pro, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro2, _mm256_srlv_epi64(gen, n2)));
+        let pro4 = _mm256_and_si256(pro2, _mm256_srlv_epi64(pro2, n2));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro4, _mm256_srlv_epi64(gen, n4)));
+        let right = _mm256_and_si256(_mm256_srlv_epi64(gen, n1), right_masks);
+
+        reduce(_mm256_or_si256(left, right)) & !(player | opponent)
+    }
+
+    #[target_feature(enable = "avx2")]
+    pub unsafe fn flips(player: u64, opponent: u64, square: usize) -> u64 {
+        let (n1, n2, n4, left_masks) = shifts();
+        let right_masks = _mm256_set_epi64x(
+            0xFEFEFEFEFEFEFEFEu64 as i64,
+            0x7F7F7F7F7F7F7F7Fu64 as i64,
+            -1,
+            0x7F7F7F7F7F7F7F7Fu64 as i64,
+        );
+
+        let p = _mm256_set1_epi64x(player as i64);
+        let o = _mm256_set1_epi64x(opponent as i64);
+        let origin = _mm256_set1_epi64x((1u64 << square) as i64);
+        let zero = _mm256_setzero_si256();
+
+        // left shifts
+        let pro = _mm256_and_si256(o, left_masks);
+        let mut gen = _mm256_and_si256(pro, _mm256_sllv_epi64(origin, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro, _mm256_sllv_epi64(gen, n1)));
+        let pro2 = _mm256_and_si256(pro, _mm256_sllv_epi64(pro, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro2, _mm256_sllv_epi64(gen, n2)));
+        let pro4 = _mm256_and_si256(pro2, _mm256_sllv_epi64(pro2, n2));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro4, _mm256_sllv_epi64(gen, n4)));
+        let capped = _mm256_and_si256(
+            _mm256_and_si256(
+                _mm256_sllv_epi64(_mm256_or_si256(gen, origin), n1),
+                left_masks,
+            ),
+            p,
+        );
+        let left = _mm256_andnot_si256(_mm256_cmpeq_epi64(capped, zero), gen);
+
+        // right shifts
+        let pro = _mm256_and_si256(o, right_masks);
+        let mut gen = _mm256_and_si256(pro, _mm256_srlv_epi64(origin, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro, _mm256_srlv_epi64(gen, n1)));
+        let pro2 = _mm256_and_si256(pro, _mm256_srlv_epi64(pro, n1));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro2, _mm256_srlv_epi64(gen, n2)));
+        let pro4 = _mm256_and_si256(pro2, _mm256_srlv_epi64(pro2, n2));
+        gen = _mm256_or_si256(gen, _mm256_and_si256(pro4, _mm256_srlv_epi64(gen, n4)));
+        let capped = _mm256_and_si256(
+            _mm256_and_si256(
+                _mm256_srlv_epi64(_mm256_or_si256(gen, origin), n1),
+                right_masks,
+            ),
+            p,
+        );
+        let right = _mm256_andnot_si256(_mm256_cmpeq_epi64(capped, zero), gen);
+
+        reduce(_mm256_or_si256(left, right))
+    }
+}
+
+impl MoveGenerator for Avx2 {
+    fn name(&self) -> &'static str {
+        "avx2"
+    }
+
+    fn moves(&self, player: &BitBoard, opponent: &BitBoard) -> BitBoard {
+        // an Avx2 value only exists when the CPU supports AVX2
+        #[cfg(target_arch = "x86_64")]
+        {
+            BitBoard::new(unsafe { avx2::moves(player.bits, opponent.bits) })
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        {
+            KoggeStone.moves(player, opponent)
+        }
+    }
+
+    fn flips(&self, player: &BitBoard, opponent: &BitBoard, square: usize) -> BitBoard {
+        #[cfg(target_arch = "x86_64")]
+        {
+            BitBoard::new(unsafe { avx2::flips(player.bits, opponent.bits, square) })
+        }
+        #[cfg(not(target_arch = "x86_64"))]
+        {
+            KoggeStone.flips(player, opponent, square)
+        }
+    }
+}
+
+/// All backends available on this CPU, the reference line cap algorithm first
+///
+/// # Examples
+/// ```
+/// use othlib::board::bitboard::BitBoard;
+/// use othlib::board::movegen::all;
+///
+/// let black = BitBoard::new(0b01000000_11011110_01000110_00101110_00011010_00011100_00000000_00000000);
+/// let white = BitBoard::new(0b00111110_00100000_00111000_00010000_00100000_00000000_00000000_00000000);
+///
+/// let backends = all();
+/// let reference = &backends[0];
+///
+/// for backend in &backends {
+///     assert_eq!(backend.moves(&white, &black), reference.moves(&white, &black));
+///     assert_eq!(backend.moves(&black, &white), reference.moves(&black, &white));
+///
+///     for square in 0..64 {
+///         assert_eq!(backend.flips(&white, &black, square), reference.flips(&white, &black, square));
+///     }
+/// }
+/// ```
+pub fn all() -> Vec<Box<dyn MoveGenerator>> {
+    let mut backends: Vec<Box<dyn MoveGenerator>> =
+        vec![Box::new(LineCap), Box::new(Shift), Box::new(KoggeStone)];
+
+    if let Some(avx2) = Avx2::new() {
+        backends.push(Box::new(avx2));
+    }
+
+    backends
+}
+
+/// The fastest backend available on this CPU: AVX2 if supported, scalar Kogge-Stone otherwise
+pub fn best() -> Box<dyn MoveGenerator> {
+    match Avx2::new() {
+        Some(avx2) => Box::new(avx2),
+        None => Box::new(KoggeStone),
+    }
+}
+
+/// Find a backend from its name
+///
+/// # Examples
+/// ```
+/// use othlib::board::movegen::by_name;
+///
+/// assert_eq!(by_name("koggestone").unwrap().name(), "koggestone");
+/// assert!(by_name("foo").is_none());
+/// ```
+pub fn by_name(name: &str) -> Option<Box<dyn MoveGenerator>> {
+    all().into_iter().find(|backend| backend.name() == name)
+}
@@ -0,0 +1,286 @@
+// Conversions between the different square and move notations:
+//
+// * bit index: 63 is A1, 0 is H8 (see BitBoard)
+// * algebric: "A1".."H8", upper or lower case
+// * WTHOR byte: 10*row + col, from 11 (A1) to 88 (H8)
+// * move lists: "F5-D6-C3" or compact "f5d6c3"
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::coordinate::{Coordinate, BOARD_SIZE};
+use crate::board::position::Move;
+
+/// Errors returned by notation conversions
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotationError {
+    /// bit index is not in 0..=63
+    InvalidBitIndex(usize),
+    /// WTHOR byte doesn't encode a square, i.e. not in 11..=88 or with a 0 or 9 digit
+    InvalidWThorByte(u8),
+    /// string is not an algebric square or a pass
+    InvalidSquare(String),
+    /// a move of a move list can't be read (ply is 0-based)
+    InvalidMove { ply: usize, text: String },
+}
+
+impl fmt::Display for NotationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotationError::InvalidBitIndex(index) => write!(f, "invalid bit index {}", index),
+            NotationError::InvalidWThorByte(byte) => write!(f, "invalid WTHOR move {}", byte),
+            NotationError::InvalidSquare(s) => write!(f, "invalid square '{}'", s),
+            NotationError::InvalidMove { ply, text } => {
+                write!(f, "invalid move '{}' at ply {}", text, ply + 1)
+            }
+        }
+    }
+}
+
+impl Error for NotationError {}
+
+/// Letter case of algebric coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    Upper,
+    Lower,
+}
+
+/// Convert a WTHOR move byte to a bit index
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::wthor_to_square;
+///
+/// assert_eq!(wthor_to_square(11), Ok(63));
+/// assert_eq!(wthor_to_square(88), Ok(0));
+/// assert_eq!(wthor_to_square(56), Ok(26));
+/// assert!(wthor_to_square(0).is_err());
+/// assert!(wthor_to_square(19).is_err());
+/// assert!(wthor_to_square(91).is_err());
+/// ```
+pub fn wthor_to_square(byte: u8) -> Result<usize, NotationError> {
+    let (row, col) = (byte / 10, byte % 10);
+
+    if !(1..=8).contains(&row) || !(1..=8).contains(&col) {
+        return Err(NotationError::InvalidWThorByte(byte));
+    }
+
+    Ok(63 - Coordinate::to_linear(((col - 1) as usize, (row - 1) as usize)))
+}
+
+/// Convert a bit index to a WTHOR move byte
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::square_to_wthor;
+///
+/// assert_eq!(square_to_wthor(63), Ok(11));
+/// assert_eq!(square_to_wthor(0), Ok(88));
+/// assert!(square_to_wthor(64).is_err());
+/// ```
+pub fn square_to_wthor(square: usize) -> Result<u8, NotationError> {
+    if square >= BOARD_SIZE * BOARD_SIZE {
+        return Err(NotationError::InvalidBitIndex(square));
+    }
+
+    let (x, y) = Coordinate::to_bitboard(square);
+    Ok((10 * (y + 1) + x + 1) as u8)
+}
+
+/// Convert an algebric square, whatever the case, to a bit index
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::algebric_to_square;
+///
+/// assert_eq!(algebric_to_square("A1"), Ok(63));
+/// assert_eq!(algebric_to_square("h8"), Ok(0));
+/// assert!(algebric_to_square("I1").is_err());
+/// assert!(algebric_to_square("A10").is_err());
+/// ```
+pub fn algebric_to_square(algebric: &str) -> Result<usize, NotationError> {
+    let bytes = algebric.trim().as_bytes();
+
+    if bytes.len() != 2 {
+        return Err(NotationError::InvalidSquare(algebric.to_string()));
+    }
+
+    let col = bytes[0].to_ascii_uppercase();
+    let row = bytes[1];
+    if !(b'A'..=b'H').contains(&col) || !(b'1'..=b'8').contains(&row) {
+        return Err(NotationError::InvalidSquare(algebric.to_string()));
+    }
+
+    Ok(63 - Coordinate::to_linear(((col - b'A') as usize, (row - b'1') as usize)))
+}
+
+/// Convert a bit index to an algebric square
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::{square_to_algebric, Case};
+///
+/// assert_eq!(square_to_algebric(63, Case::Upper), Ok("A1".to_string()));
+/// assert_eq!(square_to_algebric(26, Case::Lower), Ok("f5".to_string()));
+/// assert!(square_to_algebric(64, Case::Upper).is_err());
+/// ```
+pub fn square_to_algebric(square: usize, case: Case) -> Result<String, NotationError> {
+    if square >= BOARD_SIZE * BOARD_SIZE {
+        return Err(NotationError::InvalidBitIndex(square));
+    }
+
+    let algebric = Coordinate::to_algebric(Coordinate::to_bitboard(square));
+    Ok(match case {
+        Case::Upper => algebric,
+        Case::Lower => algebric.to_lowercase(),
+    })
+}
+
+/// Convert a WTHOR move byte to an algebric square
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::{wthor_to_algebric, Case};
+///
+/// assert_eq!(wthor_to_algebric(56, Case::Upper), Ok("F5".to_string()));
+/// assert!(wthor_to_algebric(100, Case::Upper).is_err());
+/// ```
+pub fn wthor_to_algebric(byte: u8, case: Case) -> Result<String, NotationError> {
+    square_to_algebric(wthor_to_square(byte)?, case)
+}
+
+/// Convert an algebric square to a WTHOR move byte
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::algebric_to_wthor;
+///
+/// assert_eq!(algebric_to_wthor("f5"), Ok(56));
+/// assert!(algebric_to_wthor("Z5").is_err());
+/// ```
+pub fn algebric_to_wthor(algebric: &str) -> Result<u8, NotationError> {
+    square_to_wthor(algebric_to_square(algebric)?)
+}
+
+/// Parse a single move: an algebric square or `PA`/`PASS`, whatever the case
+pub fn parse_move(text: &str) -> Result<Move, NotationError> {
+    let trimmed = text.trim();
+
+    if trimmed.eq_ignore_ascii_case("PA") || trimmed.eq_ignore_ascii_case("PASS") {
+        Ok(Move::Pass)
+    } else {
+        algebric_to_square(trimmed).map(Move::Play)
+    }
+}
+
+/// Split a move string into moves. Moves are either separated by `-`, `,` or spaces
+/// (e.g. `F5-D6-C3`) or concatenated (e.g. `f5d6c3`), whatever the case. Passes are written
+/// `PA` or `PASS`, in both notations.
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::parse_moves;
+/// use othlib::board::position::Move;
+///
+/// let moves = parse_moves("F5-D6-C3").unwrap();
+/// assert_eq!(moves, parse_moves("f5d6c3").unwrap());
+/// assert_eq!(moves, parse_moves("f5 D6, c3").unwrap());
+/// assert_eq!(moves[0].to_string(), "F5");
+///
+/// let moves = parse_moves("F5-PASS-D6").unwrap();
+/// assert_eq!(moves, parse_moves("f5pad6").unwrap());
+/// assert_eq!(moves, parse_moves("f5passd6").unwrap());
+/// assert_eq!(moves[1], Move::Pass);
+///
+/// let err = parse_moves("f5d6z9").unwrap_err();
+/// assert_eq!(err.to_string(), "invalid move 'z9' at ply 3");
+/// ```
+pub fn parse_moves(moves: &str) -> Result<Vec<Move>, NotationError> {
+    let mut parsed = Vec::new();
+
+    for token in moves.split(|c: char| c == '-' || c == ',' || c.is_whitespace()) {
+        // each token is one or more concatenated moves, of 2 chars except `PASS`
+        let chars: Vec<char> = token.chars().collect();
+        let mut start = 0;
+        while start < chars.len() {
+            let rest: String = chars[start..].iter().take(4).collect();
+            let len = if rest.eq_ignore_ascii_case("PASS") {
+                4
+            } else {
+                2
+            };
+            let text: String = chars[start..].iter().take(len).collect();
+            start += len;
+
+            let mv = parse_move(&text).map_err(|_| NotationError::InvalidMove {
+                ply: parsed.len(),
+                text: text.clone(),
+            })?;
+            parsed.push(mv);
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Write moves as algebric squares joined by `separator`. An empty separator gives the
+/// compact notation.
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::{moves_to_string, parse_moves, Case};
+///
+/// let moves = parse_moves("f5d6c3").unwrap();
+/// assert_eq!(moves_to_string(&moves, "-", Case::Upper), "F5-D6-C3");
+/// assert_eq!(moves_to_string(&moves, "", Case::Lower), "f5d6c3");
+/// ```
+pub fn moves_to_string(moves: &[Move], separator: &str, case: Case) -> String {
+    let moves: Vec<_> = moves
+        .iter()
+        .map(|mv| match case {
+            Case::Upper => mv.to_string(),
+            Case::Lower => mv.to_string().to_lowercase(),
+        })
+        .collect();
+
+    moves.join(separator)
+}
+
+/// Convert WTHOR move bytes to moves. Trailing zeros mark the end of a game and are ignored.
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::wthor_to_moves;
+///
+/// let moves = wthor_to_moves(&[56, 64, 33, 0, 0]).unwrap();
+/// assert_eq!(moves.len(), 3);
+/// assert_eq!(moves[2].to_string(), "C3");
+/// assert!(wthor_to_moves(&[56, 99]).is_err());
+/// ```
+pub fn wthor_to_moves(bytes: &[u8]) -> Result<Vec<Move>, NotationError> {
+    bytes
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| wthor_to_square(b).map(Move::Play))
+        .collect()
+}
+
+/// Convert moves to WTHOR move bytes. Passes are not stored in WTHOR files and are dropped.
+///
+/// # Examples
+/// ```
+/// use othlib::board::notation::{moves_to_wthor, parse_moves};
+///
+/// let moves = parse_moves("F5-D6-PA-C3").unwrap();
+/// assert_eq!(moves_to_wthor(&moves), Ok(vec![56, 64, 33]));
+/// ```
+pub fn moves_to_wthor(moves: &[Move]) -> Result<Vec<u8>, NotationError> {
+    moves
+        .iter()
+        .filter_map(|mv| match mv {
+            Move::Play(square) => Some(square_to_wthor(*square)),
+            Move::Pass => None,
+        })
+        .collect()
+}
@@ -0,0 +1,259 @@
+// Perft: enumerate all move paths up to a given depth to check move generation.
+//
+// A pass is a move and counts as a ply. When the game is over before reaching the requested
+// depth, the final position is a leaf node, and is also counted in `game_ends`. These are the
+// conventions giving the well-known perft numbers from the starting position.
+
+use std::ops::AddAssign;
+
+use crate::board::bitboard::BitBoard;
+use crate::board::movegen::{self, LineCap, MoveGenerator};
+use crate::board::position::{Move, Position};
+
+/// Counters collected while walking the game tree
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PerftCount {
+    /// leaf nodes: positions found at the requested depth, or games over before
+    pub nodes: u64,
+    /// passes played on the way
+    pub passes: u64,
+    /// games over before the requested depth
+    pub game_ends: u64,
+}
+
+impl AddAssign for PerftCount {
+    fn add_assign(&mut self, other: PerftCount) {
+        self.nodes += other.nodes;
+        self.passes += other.passes;
+        self.game_ends += other.game_ends;
+    }
+}
+
+// walk the tree using the player to move and its opponent
+fn perft_bits<G: MoveGenerator + ?Sized>(
+    generator: &G,
+    player: BitBoard,
+    opponent: BitBoard,
+    depth: usize,
+    count: &mut PerftCount,
+) {
+    if depth == 0 {
+        count.nodes += 1;
+        return;
+    }
+
+    let mut moves = generator.moves(&player, &opponent).bits;
+
+    if moves == 0 {
+        if generator.moves(&opponent, &player).bits == 0 {
+            count.nodes += 1;
+            count.game_ends += 1;
+        } else {
+            count.passes += 1;
+            perft_bits(generator, opponent, player, depth - 1, count);
+        }
+        return;
+    }
+
+    // no need to play the moves at the last ply
+    if depth == 1 {
+        count.nodes += u64::from(moves.count_ones());
+        return;
+    }
+
+    while moves != 0 {
+        let square = moves.trailing_zeros() as usize;
+        moves &= moves - 1;
+
+        let flipped = generator.flips(&player, &opponent, square).bits;
+        let next_player = BitBoard::new(opponent.bits & !flipped);
+        let next_opponent = BitBoard::new(player.bits | flipped | (1u64 << square));
+
+        perft_bits(generator, next_player, next_opponent, depth - 1, count);
+    }
+}
+
+/// Count the positions reached after `depth` plies, using the line cap move generator
+///
+/// # Examples
+/// ```
+/// use othlib::board::perft::perft;
+/// use othlib::board::position::Position;
+///
+/// let start = Position::new();
+/// let nodes: Vec<_> = (1..=9).map(|depth| perft(&start, depth).nodes).collect();
+/// assert_eq!(nodes, vec![4, 12, 56, 244, 1396, 8200, 55092, 390216, 3005288]);
+///
+/// // first passes are found at ply 9
+/// assert_eq!(perft(&start, 8).passes, 0);
+/// assert_eq!(perft(&start, 9).passes, 24);
+/// ```
+pub fn perft(position: &Position, depth: usize) -> PerftCount {
+    perft_with(&LineCap, position, depth)
+}
+
+/// Same as `perft`, using any move generator
+///
+/// # Examples
+/// ```
+/// use othlib::board::movegen;
+/// use othlib::board::perft::{perft, perft_with};
+/// use othlib::board::position::Position;
+///
+/// let start = Position::new();
+/// for backend in movegen::all() {
+///     assert_eq!(perft_with(backend.as_ref(), &start, 7), perft(&start, 7));
+/// }
+/// ```
+pub fn perft_with<G: MoveGenerator + ?Sized>(
+    generator: &G,
+    position: &Position,
+    depth: usize,
+) -> PerftCount {
+    let mut count = PerftCount::default();
+
+    perft_bits(
+        generator,
+        position.player(),
+        position.opponent(),
+        depth,
+        &mut count,
+    );
+
+    count
+}
+
+/// Same as `perft_with` but detailed for each move of the position. The total, also returned,
+/// is the `perft_with` count: the sum of the move counts, or the position itself when it is a
+/// leaf (at depth 0 or when the game is over) and has no move to detail.
+///
+/// # Examples
+/// ```
+/// use othlib::board::color::Color;
+/// use othlib::board::movegen::LineCap;
+/// use othlib::board::perft::{divide, perft};
+/// use othlib::board::position::{Move, Position};
+///
+/// let (counts, total) = divide(&LineCap, &Position::new(), 3);
+/// assert_eq!(counts.len(), 4);
+/// assert!(counts.iter().all(|(_, count)| count.nodes == 14));
+/// assert_eq!(total, perft(&Position::new(), 3));
+///
+/// // white can't move: the pass is detailed and counted as a ply, then black plays C1
+/// let squares = format!("XO{}", "-".repeat(62));
+/// let position = Position::from_squares(&squares, Color::White).unwrap();
+/// let (counts, total) = divide(&LineCap, &position, 2);
+/// assert_eq!(counts.len(), 1);
+/// assert_eq!(counts[0].0, Move::Pass);
+/// assert_eq!((total.nodes, total.passes), (1, 1));
+/// assert_eq!(total, perft(&position, 2));
+///
+/// // after C1 the game is over: no move, the position is a leaf node
+/// let squares = format!("XXX{}", "-".repeat(61));
+/// let position = Position::from_squares(&squares, Color::White).unwrap();
+/// let (counts, total) = divide(&LineCap, &position, 2);
+/// assert!(counts.is_empty());
+/// assert_eq!((total.nodes, total.game_ends), (1, 1));
+/// assert_eq!(total, perft(&position, 2));
+/// ```
+pub fn divide<G: MoveGenerator + ?Sized>(
+    generator: &G,
+    position: &Position,
+    depth: usize,
+) -> (Vec<(Move, PerftCount)>, PerftCount) {
+    if depth == 0 || position.is_game_over() {
+        return (Vec::new(), perft_with(generator, position, depth));
+    }
+
+    let mut moves = Vec::new();
+    let mut bits = position.legal_moves().bits;
+    while bits != 0 {
+        moves.push(Move::Play(63 - bits.leading_zeros() as usize));
+        bits &= !(1u64 << (63 - bits.leading_zeros()));
+    }
+    if moves.is_empty() {
+        moves.push(Move::Pass);
+    }
+
+    let mut total = PerftCount::default();
+    let counts = moves
+        .into_iter()
+        .map(|mv| {
+            let mut next = *position;
+            next.play(mv).unwrap();
+
+            let mut count = perft_with(generator, &next, depth - 1);
+            if mv == Move::Pass {
+                count.passes += 1;
+            }
+            total += count;
+            (mv, count)
+        })
+        .collect();
+
+    (counts, total)
+}
+
+/// Walk the tree and check that all move generators agree with the line cap algorithm, for moves
+/// and flips, on every position. The first disagreeing backend and position are returned.
+///
+/// # Examples
+/// ```
+/// use othlib::board::perft::check_generators;
+/// use othlib::board::position::Position;
+///
+/// assert_eq!(check_generators(&Position::new(), 5), Ok(()));
+/// ```
+pub fn check_generators(position: &Position, depth: usize) -> Result<(), (&'static str, Position)> {
+    let backends = movegen::all();
+    check_tree(&backends, position, depth)
+}
+
+fn check_tree(
+    backends: &[Box<dyn MoveGenerator>],
+    position: &Position,
+    depth: usize,
+) -> Result<(), (&'static str, Position)> {
+    let (player, opponent) = (position.player(), position.opponent());
+    let moves = LineCap.moves(&player, &opponent);
+
+    for backend in backends {
+        if backend.moves(&player, &opponent) != moves {
+            return Err((backend.name(), *position));
+        }
+
+        let mut bits = moves.bits;
+        while bits != 0 {
+            let square = bits.trailing_zeros() as usize;
+            bits &= bits - 1;
+
+            if backend.flips(&player, &opponent, square)
+                != LineCap.flips(&player, &opponent, square)
+            {
+                return Err((backend.name(), *position));
+            }
+        }
+    }
+
+    if depth == 0 || position.is_game_over() {
+        return Ok(());
+    }
+
+    if moves.bits == 0 {
+        let mut next = *position;
+        next.play(Move::Pass).unwrap();
+        return check_tree(backends, &next, depth - 1);
+    }
+
+    let mut bits = moves.bits;
+    while bits != 0 {
+        let square = bits.trailing_zeros() as usize;
+        bits &= bits - 1;
+
+        let mut next = *position;
+        next.play(Move::Play(square)).unwrap();
+        check_tree(backends, &next, depth - 1)?;
+    }
+
+    Ok(())
+}
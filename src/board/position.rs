@@ -0,0 +1,354 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::bitboard::BitBoard;
+use crate::board::color::Color;
+use crate::board::coordinate::{Coordinate, BOARD_SIZE};
+use crate::board::notation::{self, NotationError};
+
+/// A move is either a disc played on a bit index (63 is A1, 0 is H8), or a pass
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Move {
+    Play(usize),
+    Pass,
+}
+
+/// Display a move using algebric notation, or `PA` for a pass
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Move;
+///
+/// assert_eq!(Move::Play(63).to_string(), "A1");
+/// assert_eq!(Move::Play(26).to_string(), "F5");
+/// assert_eq!(Move::Pass.to_string(), "PA");
+/// ```
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Move::Play(square) => write!(
+                f,
+                "{}",
+                Coordinate::to_algebric(Coordinate::to_bitboard(*square))
+            ),
+            Move::Pass => write!(f, "PA"),
+        }
+    }
+}
+
+/// Parse a move from algebric notation, whatever the case. `PA` or `PASS` is a pass.
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Move;
+///
+/// assert_eq!("F5".parse::<Move>(), Ok(Move::Play(26)));
+/// assert_eq!("f5".parse::<Move>(), Ok(Move::Play(26)));
+/// assert_eq!("pa".parse::<Move>(), Ok(Move::Pass));
+/// assert!("I9".parse::<Move>().is_err());
+/// ```
+impl FromStr for Move {
+    type Err = NotationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        notation::parse_move(s)
+    }
+}
+
+/// Serialize a move as its algebric notation, like `"F5"` or `"PA"`
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Move;
+///
+/// assert_eq!(serde_json::to_string(&Move::Play(26)).unwrap(), r#""F5""#);
+/// assert_eq!(serde_json::to_string(&Move::Pass).unwrap(), r#""PA""#);
+///
+/// let bytes = bincode::serialize(&Move::Play(26)).unwrap();
+/// assert_eq!(bincode::deserialize::<Move>(&bytes).unwrap(), Move::Play(26));
+/// ```
+#[cfg(feature = "serde")]
+impl serde::Serialize for Move {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserialize a move from its algebric notation, whatever the case
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Move;
+///
+/// assert_eq!(serde_json::from_str::<Move>(r#""f5""#).unwrap(), Move::Play(26));
+/// assert_eq!(serde_json::from_str::<Move>(r#""PA""#).unwrap(), Move::Pass);
+/// assert!(serde_json::from_str::<Move>(r#""I9""#).is_err());
+/// assert!(serde_json::from_str::<Move>("26").is_err());
+/// ```
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Move {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mv = <std::borrow::Cow<str> as serde::Deserialize>::deserialize(deserializer)?;
+        mv.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Error returned when trying to play a move which is not legal in a position
+#[derive(Debug, Clone, PartialEq)]
+pub struct IllegalMove(pub Move);
+
+impl fmt::Display for IllegalMove {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "illegal move {}", self.0)
+    }
+}
+
+impl Error for IllegalMove {}
+
+/// An Othello position: black and white bitboards, and the color to move
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+///
+/// # #[cfg(feature = "serde")] {
+/// let json = serde_json::to_string(&Position::new()).unwrap();
+/// assert_eq!(
+///     json,
+///     r#"{"black":"0x0000000810000000","white":"0x0000001008000000","side":"black"}"#
+/// );
+/// assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), Position::new());
+///
+/// let msgpack = rmp_serde::to_vec(&Position::new()).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<Position>(&msgpack).unwrap(), Position::new());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Position {
+    pub black: BitBoard,
+    pub white: BitBoard,
+    pub side: Color,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Position::new()
+    }
+}
+
+impl Position {
+    /// The standard starting position: white on D4 and E5, black on D5 and E4, black to move
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::board::color::Color;
+    ///
+    /// let position = Position::new();
+    /// assert_eq!(position.nb_empty(), 60);
+    /// assert_eq!(position.side, Color::Black);
+    /// ```
+    pub fn new() -> Position {
+        Position {
+            black: BitBoard::new(0x00_00_00_08_10_00_00_00),
+            white: BitBoard::new(0x00_00_00_10_08_00_00_00),
+            side: Color::Black,
+        }
+    }
+
+    /// Pieces of the color to move
+    pub fn player(&self) -> BitBoard {
+        match self.side {
+            Color::Black => self.black,
+            Color::White => self.white,
+        }
+    }
+
+    /// Pieces of the color not to move
+    pub fn opponent(&self) -> BitBoard {
+        match self.side {
+            Color::Black => self.white,
+            Color::White => self.black,
+        }
+    }
+
+    /// Number of pieces of a color
+    pub fn count(&self, color: Color) -> u32 {
+        match color {
+            Color::Black => self.black.bits.count_ones(),
+            Color::White => self.white.bits.count_ones(),
+        }
+    }
+
+    /// Number of free squares
+    pub fn nb_empty(&self) -> u32 {
+        (self.black.bits | self.white.bits).count_zeros()
+    }
+
+    /// Legal moves for the color to move, as a bitboard
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::board::bitboard::BitBoard;
+    ///
+    /// let position = Position::new();
+    /// assert_eq!(position.legal_moves(), BitBoard::from(vec!["D3", "C4", "F5", "E6"]));
+    /// ```
+    pub fn legal_moves(&self) -> BitBoard {
+        self.player().line_cap_moves(&self.opponent())
+    }
+
+    /// True if the color to move has no legal move but the game is not over
+    pub fn must_pass(&self) -> bool {
+        self.legal_moves().bits == 0 && !self.is_game_over()
+    }
+
+    /// True if no color can play anymore
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    ///
+    /// assert!(!Position::new().is_game_over());
+    /// ```
+    pub fn is_game_over(&self) -> bool {
+        self.legal_moves().bits == 0 && self.opponent().line_cap_moves(&self.player()).bits == 0
+    }
+
+    /// Check whether a move is legal. A pass is only legal when no disc can be played.
+    pub fn is_legal(&self, mv: Move) -> bool {
+        match mv {
+            Move::Play(square) => square < 64 && self.legal_moves().bits & (1u64 << square) != 0,
+            Move::Pass => self.must_pass(),
+        }
+    }
+
+    /// Play a move for the color to move, and return the flipped pieces
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::{Move, Position};
+    /// use othlib::board::color::Color;
+    ///
+    /// let mut position = Position::new();
+    /// let flipped = position.play("F5".parse().unwrap()).unwrap();
+    ///
+    /// assert_eq!(flipped.bits.count_ones(), 1);
+    /// assert_eq!(position.count(Color::Black), 4);
+    /// assert_eq!(position.side, Color::White);
+    ///
+    /// // can't pass or play on an occupied square
+    /// assert!(position.play(Move::Pass).is_err());
+    /// assert!(position.play("F5".parse().unwrap()).is_err());
+    /// ```
+    pub fn play(&mut self, mv: Move) -> Result<BitBoard, IllegalMove> {
+        if !self.is_legal(mv) {
+            return Err(IllegalMove(mv));
+        }
+
+        let flipped = match mv {
+            Move::Play(square) => {
+                let flipped = self.player().flips(&self.opponent(), square);
+                let (mine, theirs) = match self.side {
+                    Color::Black => (&mut self.black, &mut self.white),
+                    Color::White => (&mut self.white, &mut self.black),
+                };
+                mine.bits |= flipped.bits | (1u64 << square);
+                theirs.bits &= !flipped.bits;
+                flipped
+            }
+            Move::Pass => BitBoard::zero(),
+        };
+
+        self.side = self.side.flip();
+        Ok(flipped)
+    }
+
+    /// Final disc differential from black's point of view, empty squares going to the winner
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    ///
+    /// assert_eq!(Position::new().final_score(), 0);
+    /// ```
+    pub fn final_score(&self) -> i32 {
+        let black = self.count(Color::Black) as i32;
+        let white = self.count(Color::White) as i32;
+        let empty = self.nb_empty() as i32;
+
+        match black.cmp(&white) {
+            std::cmp::Ordering::Greater => black - white + empty,
+            std::cmp::Ordering::Less => black - white - empty,
+            std::cmp::Ordering::Equal => 0,
+        }
+    }
+
+    /// Build a position from 64 squares listed from A1 to H8, using `X` or `*` for black,
+    /// `O` for white and `-` or `.` for empty squares. Whitespaces are ignored.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::board::color::Color;
+    ///
+    /// let squares = "-------- -------- -------- ---OX--- ---XO--- -------- -------- --------";
+    /// assert_eq!(Position::from_squares(squares, Color::Black), Some(Position::new()));
+    /// ```
+    pub fn from_squares(squares: &str, side: Color) -> Option<Position> {
+        let mut black = 0u64;
+        let mut white = 0u64;
+        let mut index = 0;
+
+        for c in squares.chars().filter(|c| !c.is_whitespace()) {
+            if index >= BOARD_SIZE * BOARD_SIZE {
+                return None;
+            }
+            let bit = 1u64 << (63 - index);
+            match c {
+                'X' | 'x' | '*' | 'B' | 'b' => black |= bit,
+                'O' | 'o' | 'W' | 'w' => white |= bit,
+                '-' | '.' => (),
+                _ => return None,
+            }
+            index += 1;
+        }
+
+        if index != BOARD_SIZE * BOARD_SIZE {
+            return None;
+        }
+
+        Some(Position {
+            black: BitBoard::new(black),
+            white: BitBoard::new(white),
+            side,
+        })
+    }
+
+    /// Return the 64 squares from A1 to H8, using the characters given for black, white and empty squares
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    ///
+    /// let squares = Position::new().to_squares('X', 'O', '-');
+    /// assert_eq!(&squares[24..40], "---OX------XO---");
+    /// ```
+    pub fn to_squares(&self, black: char, white: char, empty: char) -> String {
+        (0..BOARD_SIZE * BOARD_SIZE)
+            .map(|index| {
+                let bit = 1u64 << (63 - index);
+                if self.black.bits & bit != 0 {
+                    black
+                } else if self.white.bits & bit != 0 {
+                    white
+                } else {
+                    empty
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,592 @@
+// C API, built with the `capi` feature. The build script then writes its header to
+// include/othlib.h with cbindgen, and the library is built as a shared library (cdylib) to link
+// with.
+//
+// Positions, engines and WTHOR files are opaque handles created by an `oth_*_new` or
+// `oth_*_load` function and released by the matching `oth_*_free` one. Squares are bit indexes,
+// 63 being A1 and 0 being H8, and a pass is `OTH_PASS`. Functions which can fail return an
+// `OthStatus`, and never unwind into the caller.
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::ptr;
+use std::time::Duration;
+
+use crate::board::color::Color;
+use crate::board::movegen::KoggeStone;
+use crate::board::position::{Move, Position};
+use crate::engine::eval::{Evaluator, MobilityEvaluator, PatternEvaluator};
+use crate::engine::limits::SearchLimits;
+use crate::engine::probcut::ProbCut;
+use crate::engine::search::{Engine, SearchResult};
+use crate::game::record::GameRecord;
+use crate::game::wthor::{WThorFile, WThorGame};
+
+/// Version of the API, increased on incompatible changes
+pub const OTH_API_VERSION: u32 = 1;
+
+/// A pass, wherever a square is expected
+pub const OTH_PASS: i32 = -1;
+
+/// Length of the longest principal variation
+pub const OTH_MAX_PV: usize = 60;
+
+/// Result of a function which can fail
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OthStatus {
+    Ok = 0,
+    /// a pointer argument is null
+    NullPointer = -1,
+    /// an argument is out of range or can't be parsed
+    InvalidArgument = -2,
+    /// the move is not legal in the position
+    IllegalMove = -3,
+    /// a file can't be read, or has a wrong format
+    FileError = -4,
+}
+
+/// Color of the discs
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OthColor {
+    Black = 0,
+    White = 1,
+}
+
+/// Opaque position: discs and color to move
+pub struct OthPosition(Position);
+
+/// Opaque engine: evaluation, search and transposition tables
+pub struct OthEngine(Engine);
+
+/// Opaque content of a WTHOR game file
+pub struct OthWThorFile(Vec<GameRecord>);
+
+/// Limits of a search. A limit is unused when 0, and a search without any limit solves the
+/// position exactly.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OthLimits {
+    pub depth: u32,
+    pub nodes: u64,
+    pub time_ms: u64,
+}
+
+/// Outcome of a search
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct OthSearchResult {
+    /// square of the best move, or OTH_PASS
+    pub best_move: i32,
+    /// disc differential for the color to move
+    pub score: f32,
+    /// true when the score is proven
+    pub exact: bool,
+    pub depth: u32,
+    pub nodes: u64,
+    pub time_ms: u64,
+    /// expected moves from the position, the best one first
+    pub pv: [i32; OTH_MAX_PV],
+    pub pv_length: u32,
+}
+
+fn square_of(mv: Move) -> i32 {
+    match mv {
+        Move::Play(square) => square as i32,
+        Move::Pass => OTH_PASS,
+    }
+}
+
+fn move_of(square: i32) -> Option<Move> {
+    match square {
+        OTH_PASS => Some(Move::Pass),
+        0..=63 => Some(Move::Play(square as usize)),
+        _ => None,
+    }
+}
+
+// a string argument, None if null or not UTF-8
+unsafe fn str_arg<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+fn into_handle<T>(value: T) -> *mut T {
+    Box::into_raw(Box::new(value))
+}
+
+unsafe fn free_handle<T>(handle: *mut T) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+unsafe fn games<'a>(file: *const OthWThorFile) -> &'a [GameRecord] {
+    &(*file).0
+}
+
+fn search_result(result: &SearchResult) -> OthSearchResult {
+    let mut pv = [OTH_PASS; OTH_MAX_PV];
+    let length = result.pv.len().min(OTH_MAX_PV);
+    for (square, mv) in pv.iter_mut().zip(&result.pv) {
+        *square = square_of(*mv);
+    }
+
+    OthSearchResult {
+        best_move: square_of(result.best_move),
+        score: result.score,
+        exact: result.exact,
+        depth: result.depth,
+        nodes: result.nodes,
+        time_ms: result.time.as_millis() as u64,
+        pv,
+        pv_length: length as u32,
+    }
+}
+
+/// Version of the API, to check the header matches the library
+#[no_mangle]
+pub extern "C" fn oth_api_version() -> u32 {
+    OTH_API_VERSION
+}
+
+/// Legal moves of `player` against `opponent`
+#[no_mangle]
+pub extern "C" fn oth_moves(player: u64, opponent: u64) -> u64 {
+    KoggeStone::moves_bits(player, opponent)
+}
+
+/// Opponent discs flipped by `player` playing on `square`, 0 if the square is invalid
+#[no_mangle]
+pub extern "C" fn oth_flips(player: u64, opponent: u64, square: i32) -> u64 {
+    match move_of(square) {
+        Some(Move::Play(square)) => KoggeStone::flips_bits(player, opponent, square),
+        _ => 0,
+    }
+}
+
+/// Parse a move in algebric notation (`f5`, `F5`, `pa` or `pass`) into a square
+///
+/// # Safety
+/// `text` must be null or a NUL-terminated string, and `square` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_parse_move(text: *const c_char, square: *mut i32) -> OthStatus {
+    if text.is_null() || square.is_null() {
+        return OthStatus::NullPointer;
+    }
+    match str_arg(text).and_then(|text| text.parse::<Move>().ok()) {
+        Some(mv) => {
+            *square = square_of(mv);
+            OthStatus::Ok
+        }
+        None => OthStatus::InvalidArgument,
+    }
+}
+
+/// Write a square in algebric notation (e.g. `F5`, or `PA` for a pass) to a buffer of at least 3
+/// bytes, NUL included
+///
+/// # Safety
+/// `buffer` must be null or valid for `size` bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_move_to_string(
+    square: i32,
+    buffer: *mut c_char,
+    size: usize,
+) -> OthStatus {
+    if buffer.is_null() {
+        return OthStatus::NullPointer;
+    }
+    let text = match move_of(square) {
+        Some(mv) => mv.to_string(),
+        None => return OthStatus::InvalidArgument,
+    };
+    if size < text.len() + 1 {
+        return OthStatus::InvalidArgument;
+    }
+
+    ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, buffer, text.len());
+    *buffer.add(text.len()) = 0;
+    OthStatus::Ok
+}
+
+/// The starting position
+#[no_mangle]
+pub extern "C" fn oth_position_new() -> *mut OthPosition {
+    into_handle(OthPosition(Position::new()))
+}
+
+/// A position from its 64 squares from A1 to H8: `X` or `*` for black, `O` for white, `-` or `.`
+/// for empty squares, blanks being ignored. Returns null if the squares are invalid.
+///
+/// # Safety
+/// `squares` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_from_squares(
+    squares: *const c_char,
+    side: OthColor,
+) -> *mut OthPosition {
+    let side = match side {
+        OthColor::Black => Color::Black,
+        OthColor::White => Color::White,
+    };
+    match str_arg(squares).and_then(|squares| Position::from_squares(squares, side)) {
+        Some(position) => into_handle(OthPosition(position)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// A copy of a position
+///
+/// # Safety
+/// `position` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_clone(position: *const OthPosition) -> *mut OthPosition {
+    match position.as_ref() {
+        Some(position) => into_handle(OthPosition(position.0)),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Release a position
+///
+/// # Safety
+/// `position` must be null or a live handle, which can't be used after.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_free(position: *mut OthPosition) {
+    free_handle(position);
+}
+
+/// Black discs
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_black(position: *const OthPosition) -> u64 {
+    (*position).0.black.bits
+}
+
+/// White discs
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_white(position: *const OthPosition) -> u64 {
+    (*position).0.white.bits
+}
+
+/// Color to move
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_side(position: *const OthPosition) -> OthColor {
+    match (*position).0.side {
+        Color::Black => OthColor::Black,
+        Color::White => OthColor::White,
+    }
+}
+
+/// Legal moves of the color to move
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_legal_moves(position: *const OthPosition) -> u64 {
+    (*position).0.legal_moves().bits
+}
+
+/// Play a square, or OTH_PASS when the color to move has no legal move. The flipped discs are
+/// written to `flipped` unless it's null.
+///
+/// # Safety
+/// `position` must be null or a live handle, and `flipped` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_play(
+    position: *mut OthPosition,
+    square: i32,
+    flipped: *mut u64,
+) -> OthStatus {
+    let position = match position.as_mut() {
+        Some(position) => position,
+        None => return OthStatus::NullPointer,
+    };
+    let mv = match move_of(square) {
+        Some(mv) => mv,
+        None => return OthStatus::InvalidArgument,
+    };
+
+    match position.0.play(mv) {
+        Ok(discs) => {
+            if !flipped.is_null() {
+                *flipped = discs.bits;
+            }
+            OthStatus::Ok
+        }
+        Err(_) => OthStatus::IllegalMove,
+    }
+}
+
+/// True if the color to move has no legal move
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_must_pass(position: *const OthPosition) -> bool {
+    (*position).0.must_pass()
+}
+
+/// True if no color can play
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_is_game_over(position: *const OthPosition) -> bool {
+    (*position).0.is_game_over()
+}
+
+/// Number of empty squares
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_empties(position: *const OthPosition) -> u32 {
+    (*position).0.nb_empty()
+}
+
+/// Final disc differential for black, empty squares going to the winner
+///
+/// # Safety
+/// `position` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_position_final_score(position: *const OthPosition) -> i32 {
+    (*position).0.final_score()
+}
+
+/// An engine evaluating positions with the pattern weights of a weight file, or with the
+/// mobility evaluation if `weights` is null. Returns null if the weights can't be read.
+///
+/// # Safety
+/// `weights` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_new(weights: *const c_char) -> *mut OthEngine {
+    let evaluator: Box<dyn Evaluator + Send> = if weights.is_null() {
+        Box::new(MobilityEvaluator)
+    } else {
+        match str_arg(weights).and_then(|path| PatternEvaluator::load(path).ok()) {
+            Some(evaluator) => Box::new(evaluator),
+            None => return ptr::null_mut(),
+        }
+    };
+    into_handle(OthEngine(Engine::new(evaluator)))
+}
+
+/// Release an engine
+///
+/// # Safety
+/// `engine` must be null or a live handle, which can't be used after.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_free(engine: *mut OthEngine) {
+    free_handle(engine);
+}
+
+/// Number of threads of the midgame search and of the endgame solver
+///
+/// # Safety
+/// `engine` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_set_threads(engine: *mut OthEngine, threads: u32) -> OthStatus {
+    match engine.as_mut() {
+        Some(_) if threads == 0 => OthStatus::InvalidArgument,
+        Some(engine) => {
+            engine.0.set_threads(threads as usize);
+            OthStatus::Ok
+        }
+        None => OthStatus::NullPointer,
+    }
+}
+
+/// Make the midgame search selective with the Multi-ProbCut parameters of a file written by the
+/// probcut utility, or turn selectivity off if `path` is null
+///
+/// # Safety
+/// `engine` must be null or a live handle, and `path` null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_set_probcut(
+    engine: *mut OthEngine,
+    path: *const c_char,
+) -> OthStatus {
+    let engine = match engine.as_mut() {
+        Some(engine) => engine,
+        None => return OthStatus::NullPointer,
+    };
+    if path.is_null() {
+        engine.0.set_probcut(None);
+        return OthStatus::Ok;
+    }
+
+    match str_arg(path).map(ProbCut::load) {
+        Some(Ok(probcut)) => {
+            engine.0.set_probcut(Some(probcut));
+            OthStatus::Ok
+        }
+        Some(Err(_)) => OthStatus::FileError,
+        None => OthStatus::InvalidArgument,
+    }
+}
+
+/// Forget previous searches
+///
+/// # Safety
+/// `engine` must be null or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_clear(engine: *mut OthEngine) {
+    if let Some(engine) = engine.as_mut() {
+        engine.0.clear();
+    }
+}
+
+/// Search a position within limits, or solve it if `limits` is null or has no limit
+///
+/// # Safety
+/// `engine` and `position` must be null or live handles, `limits` null or valid for reads and
+/// `result` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_engine_search(
+    engine: *mut OthEngine,
+    position: *const OthPosition,
+    limits: *const OthLimits,
+    result: *mut OthSearchResult,
+) -> OthStatus {
+    let (engine, position) = match (engine.as_mut(), position.as_ref()) {
+        (Some(engine), Some(position)) => (engine, position),
+        _ => return OthStatus::NullPointer,
+    };
+    if result.is_null() {
+        return OthStatus::NullPointer;
+    }
+
+    let limits = limits.as_ref().copied().unwrap_or_default();
+    let mut search_limits = SearchLimits {
+        depth: Some(limits.depth).filter(|&d| d > 0),
+        nodes: Some(limits.nodes).filter(|&n| n > 0),
+        move_time: Some(limits.time_ms)
+            .filter(|&t| t > 0)
+            .map(Duration::from_millis),
+        ..SearchLimits::default()
+    };
+    if search_limits.is_infinite() {
+        search_limits = SearchLimits::depth(position.0.nb_empty());
+    }
+
+    *result = search_result(&engine.0.search(&position.0, &search_limits, false));
+    OthStatus::Ok
+}
+
+/// Read the games of a WTHOR game file. Returns null if the file can't be read, is truncated
+/// or a game is invalid.
+///
+/// # Safety
+/// `path` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_load(path: *const c_char) -> *mut OthWThorFile {
+    let file = match str_arg(path).map(WThorFile::<WThorGame>::new) {
+        Some(Ok(file)) => file,
+        _ => return ptr::null_mut(),
+    };
+
+    match file
+        .data
+        .iter()
+        .map(GameRecord::from_wthor)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(games) => into_handle(OthWThorFile(games)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// Release the games of a WTHOR file
+///
+/// # Safety
+/// `file` must be null or a live handle, which can't be used after.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_free(file: *mut OthWThorFile) {
+    free_handle(file);
+}
+
+/// Number of games
+///
+/// # Safety
+/// `file` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_count(file: *const OthWThorFile) -> usize {
+    games(file).len()
+}
+
+/// Write the moves of a game to `moves`, passes included, and return their number. At most
+/// `size` moves are written, the result being the full number of moves of the game, or -1 if
+/// `game` is out of range.
+///
+/// # Safety
+/// `file` must be a live handle, and `moves` null or valid for `size` writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_moves(
+    file: *const OthWThorFile,
+    game: usize,
+    moves: *mut i32,
+    size: usize,
+) -> i32 {
+    let game = match games(file).get(game) {
+        Some(game) => game,
+        None => return -1,
+    };
+    if !moves.is_null() {
+        for (i, rec) in game.moves.iter().take(size).enumerate() {
+            *moves.add(i) = square_of(rec.mv);
+        }
+    }
+    game.moves.len() as i32
+}
+
+/// Final disc differential for black of a game
+///
+/// # Safety
+/// `file` must be a live handle, and `score` null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_score(
+    file: *const OthWThorFile,
+    game: usize,
+    score: *mut i32,
+) -> OthStatus {
+    if score.is_null() {
+        return OthStatus::NullPointer;
+    }
+    match games(file).get(game) {
+        Some(game) => {
+            *score = game.result.unwrap_or(0.0).round() as i32;
+            OthStatus::Ok
+        }
+        None => OthStatus::InvalidArgument,
+    }
+}
+
+/// The position of a game before the move at `ply`, 0 being the starting position. Returns null
+/// if `game` or `ply` is out of range.
+///
+/// # Safety
+/// `file` must be a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn oth_wthor_position(
+    file: *const OthWThorFile,
+    game: usize,
+    ply: usize,
+) -> *mut OthPosition {
+    match games(file).get(game) {
+        Some(game) if ply <= game.moves.len() => into_handle(OthPosition(game.position_at(ply))),
+        _ => ptr::null_mut(),
+    }
+}
@@ -0,0 +1,669 @@
+// Exact endgame solver: negamax alpha-beta search to the end of the game, with a transposition
+// table, stability cutoffs, fastest-first move ordering and parity ordering close to the end.
+//
+// Scores are disc differentials from the point of view of the color to move, empty squares going
+// to the winner.
+//
+// With several threads, the search is a Lazy SMP: helper threads search the same position with
+// their moves in another order, and share their results with the main thread through the
+// transposition table. The main thread result is returned, and stops the helpers.
+//
+// A solve can be given a stop condition, polled by the main thread every few thousand nodes:
+// once it's true, all threads give up and no result is returned.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+
+use crate::board::movegen::KoggeStone;
+use crate::board::position::{Move, Position};
+use crate::engine::table::{TableEntry, TranspositionTable};
+
+// number of empty squares below which neither the transposition table nor sorting is worth it
+const SHALLOW_EMPTIES: u32 = 6;
+
+// transposition table size as a power of 2
+const TT_BITS: u32 = 20;
+
+// corners, then the squares next to the border, are better tried first in shallow searches
+const CORNERS: u64 = 0x8100000000000081;
+
+// nodes searched by the main thread between two polls of the stop condition
+const POLL_NODES: u64 = 4096;
+
+/// Result of a solve
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolveResult {
+    /// exact disc differential for the color to move, if both sides play perfectly
+    pub score: i32,
+    /// a move reaching the score
+    pub best_move: Move,
+    /// number of positions searched
+    pub nodes: u64,
+}
+
+/// An endgame solver keeping its transposition table between solves
+pub struct EndgameSolver {
+    threads: usize,
+    table: TranspositionTable,
+}
+
+// search state of one thread
+struct Worker<'a> {
+    table: &'a TranspositionTable,
+    stop: &'a AtomicBool,
+    // 0 for the main thread
+    id: usize,
+    nodes: u64,
+    // stop condition of the main thread, given the number of nodes searched
+    must_stop: Option<&'a mut dyn FnMut(u64) -> bool>,
+    next_poll: u64,
+}
+
+impl Default for EndgameSolver {
+    fn default() -> Self {
+        EndgameSolver::new()
+    }
+}
+
+// final score when no one can play
+#[inline]
+fn final_score(player: u64, opponent: u64) -> i32 {
+    let p = player.count_ones() as i32;
+    let o = opponent.count_ones() as i32;
+    let empty = 64 - p - o;
+
+    match p.cmp(&o) {
+        std::cmp::Ordering::Greater => p - o + empty,
+        std::cmp::Ordering::Less => p - o - empty,
+        std::cmp::Ordering::Equal => 0,
+    }
+}
+
+// board edges, with bit 63 as A1 and bit 0 as H8
+const FILE_A: u64 = 0x8080808080808080;
+const FILE_H: u64 = 0x0101010101010101;
+const RANK_1: u64 = 0xFF00000000000000;
+const RANK_8: u64 = 0x00000000000000FF;
+
+// squares whose ray is full of discs up to the edge, `forward` moving a bit one step along the
+// ray and `backward` one step back
+#[inline]
+fn full_ray(filled: u64, edge: u64, backward: impl Fn(u64) -> u64) -> u64 {
+    let mut ray = filled & edge;
+    for _ in 0..7 {
+        ray |= filled & backward(ray);
+    }
+    ray
+}
+
+// discs of `player` which can't be flipped anymore: along each of the 4 lines going through the
+// disc, the line is full, or a neighbour is a stable disc of the same color or out of the board
+fn stable_discs(player: u64, opponent: u64) -> u64 {
+    let filled = player | opponent;
+
+    let east = |b: u64| (b >> 1) & !FILE_A;
+    let west = |b: u64| (b << 1) & !FILE_H;
+    let north = |b: u64| b << 8;
+    let south = |b: u64| b >> 8;
+    let north_east = |b: u64| (b << 7) & !FILE_A;
+    let north_west = |b: u64| (b << 9) & !FILE_H;
+    let south_east = |b: u64| (b >> 9) & !FILE_A;
+    let south_west = |b: u64| (b >> 7) & !FILE_H;
+
+    let horizontal = full_ray(filled, FILE_H, west) & full_ray(filled, FILE_A, east);
+    let vertical = full_ray(filled, RANK_1, south) & full_ray(filled, RANK_8, north);
+    let diagonal = full_ray(filled, FILE_H | RANK_1, south_west)
+        & full_ray(filled, FILE_A | RANK_8, north_east);
+    let anti_diagonal = full_ray(filled, FILE_A | RANK_1, south_east)
+        & full_ray(filled, FILE_H | RANK_8, north_west);
+
+    let mut stable = 0;
+    loop {
+        let next = player
+            & (horizontal | FILE_A | FILE_H | east(stable) | west(stable))
+            & (vertical | RANK_1 | RANK_8 | north(stable) | south(stable))
+            & (diagonal
+                | FILE_A
+                | FILE_H
+                | RANK_1
+                | RANK_8
+                | north_east(stable)
+                | south_west(stable))
+            & (anti_diagonal
+                | FILE_A
+                | FILE_H
+                | RANK_1
+                | RANK_8
+                | north_west(stable)
+                | south_east(stable));
+        if next == stable {
+            return stable;
+        }
+        stable = next;
+    }
+}
+
+// empty squares next to the opponent's discs, where the player may play later
+#[inline]
+fn potential_mobility(player: u64, opponent: u64) -> i32 {
+    let empty = !(player | opponent);
+    let around = ((opponent >> 1) & !FILE_A)
+        | ((opponent << 1) & !FILE_H)
+        | (opponent << 8)
+        | (opponent >> 8)
+        | ((opponent << 7) & !FILE_A)
+        | ((opponent << 9) & !FILE_H)
+        | ((opponent >> 9) & !FILE_A)
+        | ((opponent >> 7) & !FILE_H);
+    (around & empty).count_ones() as i32
+}
+
+// the 4 quadrants of the board, to compute parity
+const QUADRANTS: [u64; 4] = [
+    0xF0F0F0F000000000,
+    0x0F0F0F0F00000000,
+    0x00000000F0F0F0F0,
+    0x000000000F0F0F0F,
+];
+
+impl EndgameSolver {
+    /// A single threaded solver
+    pub fn new() -> Self {
+        EndgameSolver::with_threads(1)
+    }
+
+    /// A solver searching with `threads` threads. A single thread gives reproducible node
+    /// counts, more threads solve faster but node counts change from one run to another.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::color::Color;
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::endgame::EndgameSolver;
+    ///
+    /// let squares = "--XXXXX- -OOOOOO- XOXXOOOX XXOXOXOX XOOXXOXX XOOOXXXX XXOOOXXO -OOOO---";
+    /// let position = Position::from_squares(squares, Color::Black).unwrap();
+    ///
+    /// let single = EndgameSolver::new().solve(&position);
+    /// let parallel = EndgameSolver::with_threads(4).solve(&position);
+    /// assert_eq!(single.score, parallel.score);
+    ///
+    /// // single threaded solves are deterministic
+    /// assert_eq!(single, EndgameSolver::new().solve(&position));
+    /// ```
+    pub fn with_threads(threads: usize) -> Self {
+        EndgameSolver {
+            threads: threads.max(1),
+            table: TranspositionTable::new(TT_BITS),
+        }
+    }
+
+    /// Number of search threads
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Change the number of search threads, at least 1
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Forget everything learnt in previous solves
+    pub fn clear(&mut self) {
+        self.table.clear();
+    }
+
+    /// Solve a position exactly
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::color::Color;
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::endgame::EndgameSolver;
+    ///
+    /// // black plays A1, flips the first row and wins everything
+    /// let squares = "-OOOOOOX XXXXXXXX XXXXXXXX XXXXXXXX XXXXXXXX XXXXXXXX XXXXXXXX XXXXXXXX";
+    /// let position = Position::from_squares(squares, Color::Black).unwrap();
+    ///
+    /// let result = EndgameSolver::new().solve(&position);
+    /// assert_eq!(result.score, 64);
+    /// assert_eq!(result.best_move.to_string(), "A1");
+    /// ```
+    pub fn solve(&mut self, position: &Position) -> SolveResult {
+        self.solve_window(position, -64, 64)
+    }
+
+    /// Solve a position unless `must_stop` becomes true. It's called every few thousand nodes
+    /// with the number of nodes searched by the main thread, e.g. to check a deadline or a stop
+    /// request. An interrupted solve returns nothing.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::color::Color;
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::endgame::EndgameSolver;
+    ///
+    /// let squares = "--XXXXX- -OOOOOO- XOXXOOOX XXOXOXOX XOOXXOXX XOOOXXXX XXOOOXXO -OOOO---";
+    /// let position = Position::from_squares(squares, Color::Black).unwrap();
+    ///
+    /// let mut solver = EndgameSolver::new();
+    /// assert_eq!(solver.solve_until(&position, |_| true), None);
+    ///
+    /// let solved = solver.solve_until(&position, |nodes| nodes > 1_000_000_000).unwrap();
+    /// assert_eq!(solved.score, solver.solve(&position).score);
+    /// ```
+    pub fn solve_until<F>(&mut self, position: &Position, mut must_stop: F) -> Option<SolveResult>
+    where
+        F: FnMut(u64) -> bool,
+    {
+        self.search_root(position, -64, 64, Some(&mut must_stop))
+    }
+
+    /// Solve a position in the (alpha, beta) window: the score is exact if it's strictly
+    /// inside the window, a bound otherwise. A null window `(s - 1, s)` tells if the score
+    /// is at least `s`.
+    pub fn solve_window(&mut self, position: &Position, alpha: i32, beta: i32) -> SolveResult {
+        self.search_root(position, alpha, beta, None).unwrap()
+    }
+
+    /// The moves of a perfect game from a position whose exact score is `score`, as returned
+    /// by a solve. The line ends when the game is over.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::color::Color;
+    /// use othlib::board::position::{Move, Position};
+    /// use othlib::engine::endgame::EndgameSolver;
+    ///
+    /// let squares = "--XXXXX- -OOOOOO- XOXXOOOX XXOXOXOX XOOXXOXX XOOOXXXX XXOOOXXO -OOOO---";
+    /// let position = Position::from_squares(squares, Color::Black).unwrap();
+    ///
+    /// let mut solver = EndgameSolver::new();
+    /// let solved = solver.solve(&position);
+    /// let pv = solver.principal_variation(&position, solved.score);
+    /// assert_eq!(pv[0], solved.best_move);
+    ///
+    /// // playing the line ends the game with the solved score
+    /// let mut end = position;
+    /// for &mv in &pv {
+    ///     end.play(mv).unwrap();
+    /// }
+    /// assert!(end.is_game_over());
+    /// let sign = if end.side == position.side { 1 } else { -1 };
+    /// let discs = end.player().bits.count_ones() as i32 - end.opponent().bits.count_ones() as i32;
+    /// assert_eq!(sign * discs, solved.score);
+    /// ```
+    pub fn principal_variation(&mut self, position: &Position, score: i32) -> Vec<Move> {
+        let stop = AtomicBool::new(false);
+        let mut worker = Worker::new(&self.table, &stop, 0, None);
+
+        let mut pv = Vec::new();
+        let (mut position, mut score) = (*position, score);
+        while !position.is_game_over() {
+            let mv = if position.must_pass() {
+                Move::Pass
+            } else {
+                // the first move whose exact score is the expected one
+                let player = position.player().bits;
+                let opponent = position.opponent().bits;
+                let moves = KoggeStone::moves_bits(player, opponent);
+                let found = worker
+                    .ordered_moves(player, opponent, moves)
+                    .into_iter()
+                    .find(|&square| {
+                        let flipped = KoggeStone::flips_bits(player, opponent, square);
+                        let next_player = opponent & !flipped;
+                        let next_opponent = player | flipped | (1u64 << square);
+                        -worker.search(next_player, next_opponent, -score - 1, -score + 1, false)
+                            == score
+                    });
+                match found {
+                    Some(square) => Move::Play(square),
+                    None => break,
+                }
+            };
+
+            position.play(mv).unwrap();
+            pv.push(mv);
+            score = -score;
+        }
+        pv
+    }
+
+    // solve with all threads, None if stopped
+    fn search_root(
+        &mut self,
+        position: &Position,
+        alpha: i32,
+        beta: i32,
+        mut must_stop: Option<&mut dyn FnMut(u64) -> bool>,
+    ) -> Option<SolveResult> {
+        let player = position.player().bits;
+        let opponent = position.opponent().bits;
+        let (table, stop) = (&self.table, AtomicBool::new(false));
+
+        thread::scope(|scope| {
+            let helpers: Vec<_> = (1..self.threads)
+                .map(|id| {
+                    let stop = &stop;
+                    scope.spawn(move || {
+                        let mut helper = Worker::new(table, stop, id, None);
+                        helper.root(player, opponent, alpha, beta);
+                        helper.nodes
+                    })
+                })
+                .collect();
+
+            let must_stop = must_stop
+                .as_mut()
+                .map(|f| &mut **f as &mut dyn FnMut(u64) -> bool);
+            let mut main = Worker::new(table, &stop, 0, must_stop);
+            let (score, best_move) = main.root(player, opponent, alpha, beta);
+            let aborted = stop.swap(true, Ordering::Relaxed);
+
+            let helper_nodes: u64 = helpers.into_iter().map(|h| h.join().unwrap()).sum();
+
+            if aborted {
+                return None;
+            }
+            Some(SolveResult {
+                score,
+                best_move,
+                nodes: main.nodes + helper_nodes,
+            })
+        })
+    }
+}
+
+impl<'a> Worker<'a> {
+    fn new(
+        table: &'a TranspositionTable,
+        stop: &'a AtomicBool,
+        id: usize,
+        must_stop: Option<&'a mut dyn FnMut(u64) -> bool>,
+    ) -> Self {
+        Worker {
+            table,
+            stop,
+            id,
+            nodes: 0,
+            must_stop,
+            next_poll: 0,
+        }
+    }
+
+    // helpers give up as soon as the main thread is done, and everyone when the stop condition
+    // of the main thread is true
+    #[inline]
+    fn stopped(&mut self) -> bool {
+        if self.nodes >= self.next_poll {
+            self.next_poll = self.nodes + POLL_NODES;
+            if let Some(must_stop) = self.must_stop.as_mut() {
+                if must_stop(self.nodes) {
+                    self.stop.store(true, Ordering::Relaxed);
+                }
+            }
+        }
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    // root search, also returning the best move
+    fn root(&mut self, player: u64, opponent: u64, mut alpha: i32, beta: i32) -> (i32, Move) {
+        self.nodes += 1;
+
+        let moves = KoggeStone::moves_bits(player, opponent);
+        if moves == 0 {
+            if KoggeStone::moves_bits(opponent, player) == 0 {
+                return (final_score(player, opponent), Move::Pass);
+            }
+            let score = -self.search(opponent, player, -beta, -alpha, true);
+            return (score, Move::Pass);
+        }
+
+        let mut best_score = -65;
+        let mut best_move = Move::Pass;
+
+        // helpers start with another move, to fill the table with other subtrees
+        let mut squares = self.ordered_moves(player, opponent, moves);
+        let len = squares.len();
+        squares.rotate_left(self.id % len);
+
+        for square in squares {
+            let flipped = KoggeStone::flips_bits(player, opponent, square);
+            let next_player = opponent & !flipped;
+            let next_opponent = player | flipped | (1u64 << square);
+
+            // principal variation search: prove other moves are worse with a null window
+            let score = if best_score == -65 {
+                -self.search(next_player, next_opponent, -beta, -alpha, false)
+            } else {
+                let mut score = -self.search(next_player, next_opponent, -alpha - 1, -alpha, false);
+                if score > alpha && score < beta {
+                    score = -self.search(next_player, next_opponent, -beta, -score, false);
+                }
+                score
+            };
+
+            if score > best_score {
+                best_score = score;
+                best_move = Move::Play(square);
+                if score > alpha {
+                    alpha = score;
+                }
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        (best_score, best_move)
+    }
+
+    // moves sorted with the hash move first, then by increasing opponent mobility and potential
+    // mobility
+    fn ordered_moves(&self, player: u64, opponent: u64, mut moves: u64) -> Vec<usize> {
+        let hash_move = self
+            .table
+            .probe(player, opponent)
+            .map(|entry| entry.best as usize);
+
+        let mut scored = Vec::with_capacity(moves.count_ones() as usize);
+        while moves != 0 {
+            let square = moves.trailing_zeros() as usize;
+            moves &= moves - 1;
+
+            let key = if Some(square) == hash_move {
+                -1000
+            } else {
+                let flipped = KoggeStone::flips_bits(player, opponent, square);
+                let next_player = opponent & !flipped;
+                let next_opponent = player | flipped | (1u64 << square);
+                let replies = KoggeStone::moves_bits(next_player, next_opponent);
+                let mobility = (replies.count_ones() + (replies & CORNERS).count_ones()) as i32;
+                let corner = if CORNERS & (1u64 << square) != 0 {
+                    -2
+                } else {
+                    0
+                };
+                mobility * 4 + corner + potential_mobility(next_player, next_opponent)
+            };
+            scored.push((key, square));
+        }
+
+        scored.sort_unstable();
+        scored.into_iter().map(|(_, square)| square).collect()
+    }
+
+    fn search(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        mut alpha: i32,
+        mut beta: i32,
+        passed: bool,
+    ) -> i32 {
+        let empties = (player | opponent).count_zeros();
+        if empties < SHALLOW_EMPTIES {
+            return self.shallow(player, opponent, alpha, beta, passed);
+        }
+
+        self.nodes += 1;
+        if self.stopped() {
+            return 0;
+        }
+
+        let moves = KoggeStone::moves_bits(player, opponent);
+        if moves == 0 {
+            if passed {
+                return final_score(player, opponent);
+            }
+            return -self.search(opponent, player, -beta, -alpha, true);
+        }
+
+        // stability cut: the opponent's stable discs are lost for good
+        if alpha > 0 {
+            let upper = 64 - 2 * stable_discs(opponent, player).count_ones() as i32;
+            if upper <= alpha {
+                return upper;
+            }
+            beta = beta.min(upper);
+        }
+
+        // transposition table cut
+        if let Some(entry) = self.table.probe(player, opponent) {
+            let (lower, upper) = (i32::from(entry.lower), i32::from(entry.upper));
+            if lower >= beta {
+                return lower;
+            }
+            if upper <= alpha {
+                return upper;
+            }
+            if lower == upper {
+                return lower;
+            }
+            alpha = alpha.max(lower);
+            beta = beta.min(upper);
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -65;
+        let mut best_square = 0;
+
+        for square in self.ordered_moves(player, opponent, moves) {
+            let flipped = KoggeStone::flips_bits(player, opponent, square);
+            let next_player = opponent & !flipped;
+            let next_opponent = player | flipped | (1u64 << square);
+
+            let score = if best_score == -65 {
+                -self.search(next_player, next_opponent, -beta, -alpha, false)
+            } else {
+                let mut score = -self.search(next_player, next_opponent, -alpha - 1, -alpha, false);
+                if score > alpha && score < beta {
+                    score = -self.search(next_player, next_opponent, -beta, -score, false);
+                }
+                score
+            };
+
+            if score > best_score {
+                best_score = score;
+                best_square = square;
+                if score > alpha {
+                    alpha = score;
+                }
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+
+        // a stopped search gives wrong scores, which must not be stored
+        if self.stopped() {
+            return 0;
+        }
+
+        // store bounds
+        let (lower, upper) = if best_score <= original_alpha {
+            (-64, best_score)
+        } else if best_score >= beta {
+            (best_score, 64)
+        } else {
+            (best_score, best_score)
+        };
+        self.table.store(
+            player,
+            opponent,
+            TableEntry {
+                lower: lower as i16,
+                upper: upper as i16,
+                depth: empties as u8,
+                best: best_square as u8,
+            },
+        );
+
+        best_score
+    }
+
+    // search close to the end: no table, moves in odd quadrants and corners first
+    fn shallow(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        mut alpha: i32,
+        beta: i32,
+        passed: bool,
+    ) -> i32 {
+        self.nodes += 1;
+
+        let empty = !(player | opponent);
+        let moves = KoggeStone::moves_bits(player, opponent);
+        if moves == 0 {
+            if passed || empty == 0 {
+                return final_score(player, opponent);
+            }
+            return -self.shallow(opponent, player, -beta, -alpha, true);
+        }
+
+        // odd quadrants first
+        let mut odd = 0u64;
+        for quadrant in &QUADRANTS {
+            if (empty & quadrant).count_ones() & 1 == 1 {
+                odd |= quadrant;
+            }
+        }
+
+        let mut best_score = -65;
+        for group in &[
+            moves & odd & CORNERS,
+            moves & odd & !CORNERS,
+            moves & !odd & CORNERS,
+            moves & !odd & !CORNERS,
+        ] {
+            let mut bits = *group;
+            while bits != 0 {
+                let square = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+
+                let flipped = KoggeStone::flips_bits(player, opponent, square);
+                let score = -self.shallow(
+                    opponent & !flipped,
+                    player | flipped | (1u64 << square),
+                    -beta,
+                    -alpha,
+                    false,
+                );
+
+                if score > best_score {
+                    best_score = score;
+                    if score > alpha {
+                        alpha = score;
+                        if alpha >= beta {
+                            return best_score;
+                        }
+                    }
+                }
+            }
+        }
+
+        best_score
+    }
+}
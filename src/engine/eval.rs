@@ -0,0 +1,400 @@
+// Pattern-based evaluation, as in Logistello or Edax.
+//
+// A pattern is a set of squares. Each square being empty, the player's or the opponent's, the
+// content of a pattern is a base 3 number used as an index in a table of weights. Patterns are
+// matched on the 8 symmetries of the board, so that a single table covers the 4 corners or the 4
+// edges. Weights depend on the stage of the game, given by the number of discs, and the sum of
+// the weights is an estimate of the final disc differential for the color to move.
+//
+// Weights are stored in a little endian binary file:
+//
+// * magic `OTHW`, then format version, number of stages and number of weights per stage as u32
+// * for each stage, a f32 bias followed by the f32 weights of all patterns
+
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+
+use crate::board::bitboard::BitBoard;
+use crate::board::movegen::KoggeStone;
+use crate::board::position::Position;
+
+/// A set of squares whose content is evaluated as a whole
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pattern {
+    pub name: &'static str,
+    /// number of squares
+    pub size: usize,
+}
+
+/// Patterns, as found in the top left corner or on the top edge
+pub const PATTERNS: [Pattern; 11] = [
+    Pattern {
+        name: "edge + 2X",
+        size: 10,
+    },
+    Pattern {
+        name: "corner 2x5",
+        size: 10,
+    },
+    Pattern {
+        name: "corner 3x3",
+        size: 9,
+    },
+    Pattern {
+        name: "row 2",
+        size: 8,
+    },
+    Pattern {
+        name: "row 3",
+        size: 8,
+    },
+    Pattern {
+        name: "row 4",
+        size: 8,
+    },
+    Pattern {
+        name: "diagonal 8",
+        size: 8,
+    },
+    Pattern {
+        name: "diagonal 7",
+        size: 7,
+    },
+    Pattern {
+        name: "diagonal 6",
+        size: 6,
+    },
+    Pattern {
+        name: "diagonal 5",
+        size: 5,
+    },
+    Pattern {
+        name: "diagonal 4",
+        size: 4,
+    },
+];
+
+/// Number of game stages, each one having its own weights
+pub const STAGES: usize = 15;
+
+/// Number of pattern occurrences evaluated in a position: each pattern on each symmetry
+pub const FEATURES: usize = 8 * PATTERNS.len();
+
+const fn pow3(n: usize) -> usize {
+    let mut p = 1;
+    let mut i = 0;
+    while i < n {
+        p *= 3;
+        i += 1;
+    }
+    p
+}
+
+// index of the first weight of each pattern in a stage
+const fn offsets() -> [usize; PATTERNS.len() + 1] {
+    let mut offsets = [0; PATTERNS.len() + 1];
+    let mut i = 0;
+    while i < PATTERNS.len() {
+        offsets[i + 1] = offsets[i] + pow3(PATTERNS[i].size);
+        i += 1;
+    }
+    offsets
+}
+
+const OFFSETS: [usize; PATTERNS.len() + 1] = offsets();
+
+/// Number of weights for a stage
+pub const STAGE_SIZE: usize = OFFSETS[PATTERNS.len()];
+
+// value of the 10 bits numbers read in base 3
+const fn base3_table() -> [u16; 1024] {
+    let mut table = [0u16; 1024];
+    let mut bits = 0;
+    while bits < 1024 {
+        let mut value = 0;
+        let mut i = 9;
+        loop {
+            value = value * 3 + ((bits >> i) & 1) as u16;
+            if i == 0 {
+                break;
+            }
+            i -= 1;
+        }
+        table[bits] = value;
+        bits += 1;
+    }
+    table
+}
+
+const BASE3: [u16; 1024] = base3_table();
+
+const FORMAT_MAGIC: &[u8; 4] = b"OTHW";
+const FORMAT_VERSION: u32 = 1;
+
+/// The stage of a position, from its number of discs
+pub fn stage(player: u64, opponent: u64) -> usize {
+    let discs = (player | opponent).count_ones() as usize;
+    (discs.saturating_sub(4) / 4).min(STAGES - 1)
+}
+
+// squares of a pattern as a number, the first square being the most significant bit
+fn pattern_bits(board: &BitBoard, pattern: usize) -> usize {
+    let (row0, row1, row2) = (
+        board.row_bits(0) as usize,
+        board.row_bits(1) as usize,
+        board.row_bits(2) as usize,
+    );
+
+    match pattern {
+        // A1..H1, B2, G2
+        0 => (row0 << 2) | ((row1 >> 5) & 2) | ((row1 >> 1) & 1),
+        1 => ((row0 >> 3) << 5) | (row1 >> 3),
+        2 => ((row0 >> 5) << 6) | ((row1 >> 5) << 3) | (row2 >> 5),
+        3 => row1,
+        4 => row2,
+        5 => board.row_bits(3) as usize,
+        _ => board.diagonal_bits((pattern - 6) as isize) as usize,
+    }
+}
+
+/// Indexes of the weights of a position in its stage: one for each pattern and symmetry
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+/// use othlib::engine::eval::{features, STAGE_SIZE};
+///
+/// let start = Position::new();
+/// let features = features(start.player().bits, start.opponent().bits);
+///
+/// assert!(features.iter().all(|&f| f < STAGE_SIZE));
+/// // the edges are empty
+/// assert_eq!(features[0], 0);
+/// ```
+pub fn features(player: u64, opponent: u64) -> [usize; FEATURES] {
+    let players = BitBoard::new(player).symmetries();
+    let opponents = BitBoard::new(opponent).symmetries();
+    let mut features = [0; FEATURES];
+
+    for (symmetry, (p, o)) in players.iter().zip(opponents.iter()).enumerate() {
+        for pattern in 0..PATTERNS.len() {
+            let index = BASE3[pattern_bits(p, pattern)] as usize
+                + 2 * BASE3[pattern_bits(o, pattern)] as usize;
+            features[symmetry * PATTERNS.len() + pattern] = OFFSETS[pattern] + index;
+        }
+    }
+
+    features
+}
+
+/// Anything able to estimate the final disc differential of a position, for the color to move
+pub trait Evaluator: Sync {
+    fn evaluate(&self, player: u64, opponent: u64) -> f32;
+
+    fn evaluate_position(&self, position: &Position) -> f32 {
+        self.evaluate(position.player().bits, position.opponent().bits)
+    }
+}
+
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+/// A hand-written evaluation from mobility and corners, to play without a weight file
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+/// use othlib::engine::eval::{Evaluator, MobilityEvaluator};
+///
+/// assert_eq!(MobilityEvaluator.evaluate_position(&Position::new()), 0.0);
+///
+/// // a corner is worth a few moves
+/// let player = 1u64 << 63;
+/// let opponent = 0x0000_0018_1800_0000;
+/// assert!(MobilityEvaluator.evaluate(player, opponent) > 0.0);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MobilityEvaluator;
+
+impl Evaluator for MobilityEvaluator {
+    fn evaluate(&self, player: u64, opponent: u64) -> f32 {
+        let mobility = KoggeStone::moves_bits(player, opponent).count_ones() as f32
+            - KoggeStone::moves_bits(opponent, player).count_ones() as f32;
+        let corners =
+            (player & CORNERS).count_ones() as f32 - (opponent & CORNERS).count_ones() as f32;
+
+        // X-squares next to an empty corner
+        let empty_corners = CORNERS & !(player | opponent);
+        let x_squares = ((empty_corners & (1 << 63)) >> 9)
+            | ((empty_corners & (1 << 56)) >> 7)
+            | ((empty_corners & (1 << 7)) << 7)
+            | ((empty_corners & 1) << 9);
+        let dangers =
+            (player & x_squares).count_ones() as f32 - (opponent & x_squares).count_ones() as f32;
+
+        mobility + 4.0 * corners - 2.0 * dangers
+    }
+}
+
+/// Errors found when reading or writing a weight file
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvalError {
+    Io(String),
+    /// not a weight file, or not made for these patterns
+    Format(String),
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Io(e) => write!(f, "I/O error: {}", e),
+            EvalError::Format(e) => write!(f, "invalid weight file: {}", e),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+impl From<std::io::Error> for EvalError {
+    fn from(e: std::io::Error) -> Self {
+        EvalError::Io(e.to_string())
+    }
+}
+
+/// Pattern weights for all stages
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternEvaluator {
+    /// `STAGES` blocks of `STAGE_SIZE` weights
+    pub weights: Vec<f32>,
+    /// a constant for each stage
+    pub bias: Vec<f32>,
+}
+
+impl Default for PatternEvaluator {
+    fn default() -> Self {
+        PatternEvaluator::zero()
+    }
+}
+
+impl PatternEvaluator {
+    /// An evaluator with all weights set to 0
+    pub fn zero() -> Self {
+        PatternEvaluator {
+            weights: vec![0.0; STAGES * STAGE_SIZE],
+            bias: vec![0.0; STAGES],
+        }
+    }
+
+    /// Weights of a stage
+    pub fn stage_weights(&self, stage: usize) -> &[f32] {
+        &self.weights[stage * STAGE_SIZE..(stage + 1) * STAGE_SIZE]
+    }
+
+    /// Mutable weights of a stage
+    pub fn stage_weights_mut(&mut self, stage: usize) -> &mut [f32] {
+        &mut self.weights[stage * STAGE_SIZE..(stage + 1) * STAGE_SIZE]
+    }
+
+    /// Read weights from a weight file
+    pub fn load(path: &str) -> Result<Self, EvalError> {
+        PatternEvaluator::read(&mut BufReader::new(File::open(path)?))
+    }
+
+    /// Write weights to a weight file
+    pub fn save(&self, path: &str) -> Result<(), EvalError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()?;
+        Ok(())
+    }
+
+    /// Read weights in the weight file format
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, EvalError> {
+        let mut magic = [0u8; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != FORMAT_MAGIC {
+            return Err(EvalError::Format("bad magic number".to_string()));
+        }
+
+        let version = reader.read_u32::<LittleEndian>()?;
+        let stages = reader.read_u32::<LittleEndian>()? as usize;
+        let size = reader.read_u32::<LittleEndian>()? as usize;
+        if version != FORMAT_VERSION || stages != STAGES || size != STAGE_SIZE {
+            return Err(EvalError::Format(format!(
+                "version {}, {} stages of {} weights, expected version {}, {} stages of {} weights",
+                version, stages, size, FORMAT_VERSION, STAGES, STAGE_SIZE
+            )));
+        }
+
+        let mut evaluator = PatternEvaluator::zero();
+        for stage in 0..STAGES {
+            evaluator.bias[stage] = reader.read_f32::<LittleEndian>()?;
+            reader.read_f32_into::<LittleEndian>(evaluator.stage_weights_mut(stage))?;
+        }
+
+        Ok(evaluator)
+    }
+
+    /// Write weights in the weight file format
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::PatternEvaluator;
+    ///
+    /// let mut evaluator = PatternEvaluator::zero();
+    /// evaluator.bias[3] = 1.5;
+    /// evaluator.stage_weights_mut(2)[42] = -0.25;
+    ///
+    /// let mut file = Vec::new();
+    /// evaluator.write(&mut file).unwrap();
+    /// assert_eq!(PatternEvaluator::read(&mut file.as_slice()).unwrap(), evaluator);
+    ///
+    /// assert!(PatternEvaluator::read(&mut &file[1..]).is_err());
+    /// ```
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), EvalError> {
+        writer.write_all(FORMAT_MAGIC)?;
+        writer.write_u32::<LittleEndian>(FORMAT_VERSION)?;
+        writer.write_u32::<LittleEndian>(STAGES as u32)?;
+        writer.write_u32::<LittleEndian>(STAGE_SIZE as u32)?;
+
+        for stage in 0..STAGES {
+            writer.write_f32::<LittleEndian>(self.bias[stage])?;
+            for weight in self.stage_weights(stage) {
+                writer.write_f32::<LittleEndian>(*weight)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Sum of the bias and weights of the position stage
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+/// use othlib::engine::eval::{features, stage, Evaluator, PatternEvaluator};
+///
+/// let position = Position::new();
+/// let (player, opponent) = (position.player().bits, position.opponent().bits);
+///
+/// let mut evaluator = PatternEvaluator::zero();
+/// evaluator.bias[stage(player, opponent)] = 1.0;
+/// evaluator.stage_weights_mut(0)[features(player, opponent)[0]] = 0.5;
+///
+/// // the empty edge pattern is found on the 8 symmetries
+/// assert_eq!(evaluator.evaluate_position(&position), 5.0);
+/// ```
+impl Evaluator for PatternEvaluator {
+    fn evaluate(&self, player: u64, opponent: u64) -> f32 {
+        let stage = stage(player, opponent);
+        let weights = self.stage_weights(stage);
+
+        features(player, opponent)
+            .iter()
+            .fold(self.bias[stage], |acc, &f| acc + weights[f])
+    }
+}
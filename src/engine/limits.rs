@@ -0,0 +1,269 @@
+// Search limits and time management.
+//
+// A search stops at the first limit reached: depth, nodes, time, or a stop request from another
+// thread. With a clock, the time manager gives each move a share of the remaining time, taking
+// into account that the last moves are solved almost instantly.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// std::time::Instant panics in the browser
+#[cfg(not(feature = "wasm"))]
+use std::time::Instant;
+#[cfg(feature = "wasm")]
+use web_time::Instant;
+
+// the last empties are solved in no time, and don't need a share of the clock
+const INSTANT_EMPTIES: u32 = 12;
+
+// time kept on the clock for communication delays
+const SAFETY_MARGIN: Duration = Duration::from_millis(50);
+
+// a move may use this many times its budget if the search needs it
+const HARD_LIMIT_FACTOR: u32 = 4;
+
+/// Time left on the clock of the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clock {
+    /// time left for the rest of the game, or until the next time control
+    pub remaining: Duration,
+    /// time added after each move
+    pub increment: Duration,
+    /// number of moves to play before the next time control, if any
+    pub moves_to_go: Option<u32>,
+}
+
+/// Limits of a search. When no limit is set, the search runs until stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchLimits {
+    /// maximum depth in plies
+    pub depth: Option<u32>,
+    /// maximum number of nodes
+    pub nodes: Option<u64>,
+    /// fixed time per move
+    pub move_time: Option<Duration>,
+    /// tournament clock
+    pub clock: Option<Clock>,
+}
+
+impl SearchLimits {
+    /// Search until stopped
+    pub fn infinite() -> Self {
+        SearchLimits::default()
+    }
+
+    /// Search to a fixed depth
+    pub fn depth(depth: u32) -> Self {
+        SearchLimits {
+            depth: Some(depth),
+            ..SearchLimits::default()
+        }
+    }
+
+    /// Search a fixed number of nodes
+    pub fn nodes(nodes: u64) -> Self {
+        SearchLimits {
+            nodes: Some(nodes),
+            ..SearchLimits::default()
+        }
+    }
+
+    /// Search for a fixed time
+    pub fn move_time(time: Duration) -> Self {
+        SearchLimits {
+            move_time: Some(time),
+            ..SearchLimits::default()
+        }
+    }
+
+    /// Play on a clock
+    pub fn clock(remaining: Duration, increment: Duration, moves_to_go: Option<u32>) -> Self {
+        SearchLimits {
+            clock: Some(Clock {
+                remaining,
+                increment,
+                moves_to_go,
+            }),
+            ..SearchLimits::default()
+        }
+    }
+
+    /// True if the search can only be ended by a stop request
+    pub fn is_infinite(&self) -> bool {
+        self.depth.is_none()
+            && self.nodes.is_none()
+            && self.move_time.is_none()
+            && self.clock.is_none()
+    }
+}
+
+/// Cooperative stop and ponder flags, shared between a search and the threads controlling it
+#[derive(Debug, Default)]
+pub struct SearchControl {
+    stop: AtomicBool,
+    ponder: AtomicBool,
+}
+
+impl SearchControl {
+    pub fn new() -> Self {
+        SearchControl::default()
+    }
+
+    /// Ask the running search to return as soon as possible
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+
+    /// True if a stop was requested
+    pub fn is_stopped(&self) -> bool {
+        self.stop.load(Ordering::Relaxed)
+    }
+
+    /// Search while the opponent thinks: time limits are ignored until `ponder_hit`
+    pub fn start_pondering(&self) {
+        self.ponder.store(true, Ordering::Relaxed);
+    }
+
+    /// The opponent played the expected move: the search goes on, and its clock starts now
+    pub fn ponder_hit(&self) {
+        self.ponder.store(false, Ordering::Relaxed);
+    }
+
+    /// True while pondering
+    pub fn is_pondering(&self) -> bool {
+        self.ponder.load(Ordering::Relaxed)
+    }
+
+    /// Clear both flags before a new search
+    pub fn reset(&self) {
+        self.stop.store(false, Ordering::Relaxed);
+        self.ponder.store(false, Ordering::Relaxed);
+    }
+}
+
+/// Time allocated to a move: the search should not start a new iteration after the soft limit,
+/// and must return at the hard limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeBudget {
+    pub soft: Duration,
+    pub hard: Duration,
+}
+
+/// Share a clock between the moves left to play
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use othlib::engine::limits::{time_budget, SearchLimits};
+///
+/// let minute = Duration::from_secs(60);
+///
+/// // more time per move when fewer moves are left
+/// let early = time_budget(&SearchLimits::clock(minute, Duration::from_secs(0), None), 50).unwrap();
+/// let late = time_budget(&SearchLimits::clock(minute, Duration::from_secs(0), None), 24).unwrap();
+/// assert!(early.soft < late.soft);
+/// assert!(late.hard < minute);
+///
+/// // a fixed time per move is used as is
+/// let fixed = time_budget(&SearchLimits::move_time(minute), 50).unwrap();
+/// assert_eq!(fixed.hard, minute);
+///
+/// assert_eq!(time_budget(&SearchLimits::depth(10), 50), None);
+/// ```
+pub fn time_budget(limits: &SearchLimits, empties: u32) -> Option<TimeBudget> {
+    let clock_budget = limits.clock.map(|clock| {
+        let available = clock
+            .remaining
+            .checked_sub(SAFETY_MARGIN)
+            .unwrap_or_default();
+
+        // our moves left, not counting the ones solved instantly
+        let mut moves_left = empties.saturating_sub(INSTANT_EMPTIES).div_ceil(2);
+        if let Some(moves_to_go) = clock.moves_to_go {
+            moves_left = moves_left.min(moves_to_go);
+        }
+        let moves_left = moves_left.max(1);
+
+        let soft = (available / moves_left + clock.increment * 3 / 4).min(available);
+        let hard = (soft * HARD_LIMIT_FACTOR).min(available / 2).max(soft);
+        TimeBudget { soft, hard }
+    });
+
+    let fixed_budget = limits.move_time.map(|time| TimeBudget {
+        soft: time,
+        hard: time,
+    });
+
+    match (clock_budget, fixed_budget) {
+        (Some(clock), Some(fixed)) => Some(TimeBudget {
+            soft: clock.soft.min(fixed.soft),
+            hard: clock.hard.min(fixed.hard),
+        }),
+        (budget, None) | (None, budget) => budget,
+    }
+}
+
+/// Decide when a running search must stop
+pub struct TimeManager<'a> {
+    limits: SearchLimits,
+    budget: Option<TimeBudget>,
+    control: &'a SearchControl,
+    start: Instant,
+}
+
+impl<'a> TimeManager<'a> {
+    pub fn new(limits: &SearchLimits, empties: u32, control: &'a SearchControl) -> Self {
+        TimeManager {
+            limits: *limits,
+            budget: time_budget(limits, empties),
+            control,
+            start: Instant::now(),
+        }
+    }
+
+    /// Time spent since the search started, or since the ponder hit
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    // while pondering, the clock doesn't run
+    fn update_ponder(&mut self) -> bool {
+        if self.control.is_pondering() {
+            self.start = Instant::now();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// True if the search must stop now
+    pub fn must_stop(&mut self, nodes: u64) -> bool {
+        if self.control.is_stopped() {
+            return true;
+        }
+        if self.limits.nodes.is_some_and(|max| nodes >= max) {
+            return true;
+        }
+        if self.update_ponder() {
+            return false;
+        }
+        self.budget
+            .is_some_and(|budget| self.elapsed() >= budget.hard)
+    }
+
+    /// True if an iteration at `depth` may be started
+    pub fn can_start_iteration(&mut self, depth: u32, nodes: u64) -> bool {
+        if self.must_stop(nodes) {
+            return false;
+        }
+        if self.limits.depth.is_some_and(|max| depth > max) {
+            return false;
+        }
+        if self.update_ponder() {
+            return true;
+        }
+        // the next iteration will probably take longer than all previous ones
+        self.budget
+            .is_none_or(|budget| self.elapsed() < budget.soft / 2)
+    }
+}
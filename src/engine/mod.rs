@@ -0,0 +1,9 @@
+pub mod endgame;
+pub mod eval;
+pub mod limits;
+pub mod ordering;
+pub mod probcut;
+pub mod search;
+pub mod table;
+pub mod tournament;
+pub mod train;
@@ -0,0 +1,252 @@
+// Move ordering for the midgame search.
+//
+// Alpha-beta cuts more when the best move is searched first. Moves are taken from the legal move
+// mask (as computed by `BitBoard::line_cap_moves` or any other move generator) and sorted by a key
+// mixing several heuristics, each of which can be turned off to measure its effect on node counts:
+//
+// * hash move: the best move found by a previous search of the position is always first
+// * shallow search: moves are sorted by the score of a shallow search, when the remaining depth
+//   makes it worth it
+// * killers: the last two moves which caused a cutoff at the same ply
+// * mobility: moves leaving few replies to the opponent
+// * history: moves which often caused cutoffs anywhere in the tree
+// * square priority: corners first, X-squares last
+
+use crate::board::movegen::KoggeStone;
+
+// weight of each heuristic in the sort key: a shallow search score dominates everything, then
+// killers, then a mix of mobility, history and square priority
+const SHALLOW_WEIGHT: i64 = 1 << 20;
+const KILLER_WEIGHT: i64 = 1 << 16;
+const MOBILITY_WEIGHT: i64 = 1 << 9;
+const SQUARE_WEIGHT: i64 = 1 << 8;
+
+// history counters are halved when one of them reaches this value
+const HISTORY_MAX: u32 = 1 << 16;
+
+// deepest ply with killer moves
+const MAX_PLY: usize = 128;
+
+/// Static value of each square, by bit index: corners first, X-squares last
+#[rustfmt::skip]
+pub const SQUARE_PRIORITY: [i32; 64] = [
+    20,  -4,   6,   4,   4,   6,  -4,  20,
+    -4, -20,  -2,  -2,  -2,  -2, -20,  -4,
+     6,  -2,   2,   1,   1,   2,  -2,   6,
+     4,  -2,   1,   0,   0,   1,  -2,   4,
+     4,  -2,   1,   0,   0,   1,  -2,   4,
+     6,  -2,   2,   1,   1,   2,  -2,   6,
+    -4, -20,  -2,  -2,  -2,  -2, -20,  -4,
+    20,  -4,   6,   4,   4,   6,  -4,  20,
+];
+
+/// Heuristics used to sort moves. Each one can be turned off to measure its gain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrderingOptions {
+    pub hash_move: bool,
+    pub square_priority: bool,
+    pub mobility: bool,
+    pub history: bool,
+    pub killers: bool,
+    /// depth of the ordering search, 0 to disable it
+    pub shallow_depth: u32,
+    /// minimum remaining depth for an ordering search
+    pub shallow_min_depth: u32,
+}
+
+impl Default for OrderingOptions {
+    fn default() -> Self {
+        OrderingOptions::all()
+    }
+}
+
+impl OrderingOptions {
+    /// All heuristics
+    pub fn all() -> Self {
+        OrderingOptions {
+            hash_move: true,
+            square_priority: true,
+            mobility: true,
+            history: true,
+            killers: true,
+            shallow_depth: 2,
+            shallow_min_depth: 7,
+        }
+    }
+
+    /// No ordering at all: moves are searched by bit index
+    pub fn none() -> Self {
+        OrderingOptions {
+            hash_move: false,
+            square_priority: false,
+            mobility: false,
+            history: false,
+            killers: false,
+            shallow_depth: 0,
+            shallow_min_depth: 0,
+        }
+    }
+
+    /// True if an ordering search is done at this remaining depth
+    pub fn use_shallow_search(&self, depth: u32) -> bool {
+        self.shallow_depth > 0 && depth >= self.shallow_min_depth.max(self.shallow_depth + 1)
+    }
+}
+
+/// Sorts moves, and learns from cutoffs through the history and killer heuristics
+#[derive(Debug, Clone)]
+pub struct MoveOrderer {
+    pub options: OrderingOptions,
+    history: [u32; 64],
+    killers: Vec<[Option<u8>; 2]>,
+}
+
+impl Default for MoveOrderer {
+    fn default() -> Self {
+        MoveOrderer::new(OrderingOptions::default())
+    }
+}
+
+impl MoveOrderer {
+    pub fn new(options: OrderingOptions) -> Self {
+        MoveOrderer {
+            options,
+            history: [0; 64],
+            killers: vec![[None; 2]; MAX_PLY],
+        }
+    }
+
+    /// Forget history and killers
+    pub fn clear(&mut self) {
+        self.history = [0; 64];
+        for killers in self.killers.iter_mut() {
+            *killers = [None; 2];
+        }
+    }
+
+    /// Record a move causing a cutoff at a ply, with the remaining depth
+    pub fn record_cutoff(&mut self, ply: usize, square: usize, depth: u32) {
+        if self.options.history {
+            self.history[square] += depth * depth;
+            if self.history[square] >= HISTORY_MAX {
+                for h in self.history.iter_mut() {
+                    *h /= 2;
+                }
+            }
+        }
+
+        if self.options.killers {
+            let killers = &mut self.killers[ply.min(MAX_PLY - 1)];
+            if killers[0] != Some(square as u8) {
+                killers[1] = killers[0];
+                killers[0] = Some(square as u8);
+            }
+        }
+    }
+
+    /// Sort the legal moves of a position, best first. `shallow_scores`, indexed by square, are
+    /// the results of an ordering search if one was done.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::bitboard::BitBoard;
+    /// use othlib::engine::ordering::{MoveOrderer, OrderingOptions};
+    ///
+    /// // black on C3 can take the A1 corner through B2, or play on A3 through B3
+    /// let black = BitBoard::new(1 << 45);
+    /// let white = BitBoard::new((1 << 54) | (1 << 46));
+    /// let moves = black.line_cap_moves(&white);
+    ///
+    /// let mut options = OrderingOptions::none();
+    /// let orderer = MoveOrderer::new(options);
+    /// let natural = orderer.order(black.bits, white.bits, moves.bits, None, 0, None);
+    /// assert_eq!(natural, vec![47, 63]);
+    ///
+    /// options.square_priority = true;
+    /// let orderer = MoveOrderer::new(options);
+    /// let sorted = orderer.order(black.bits, white.bits, moves.bits, None, 0, None);
+    /// assert_eq!(sorted[0], 63);
+    ///
+    /// // the hash move always comes first
+    /// options.hash_move = true;
+    /// let orderer = MoveOrderer::new(options);
+    /// let sorted = orderer.order(black.bits, white.bits, moves.bits, Some(sorted[1]), 0, None);
+    /// assert_eq!(sorted[1], 63);
+    /// ```
+    pub fn order(
+        &self,
+        player: u64,
+        opponent: u64,
+        mut moves: u64,
+        hash_move: Option<usize>,
+        ply: usize,
+        shallow_scores: Option<&[i32; 64]>,
+    ) -> Vec<usize> {
+        let mut squares = Vec::with_capacity(moves.count_ones() as usize);
+
+        if let Some(square) = hash_move.filter(|_| self.options.hash_move) {
+            if moves & (1u64 << square) != 0 {
+                squares.push(square);
+                moves &= !(1u64 << square);
+            }
+        }
+
+        let mut keyed = Vec::with_capacity(moves.count_ones() as usize);
+        while moves != 0 {
+            let square = moves.trailing_zeros() as usize;
+            keyed.push((
+                self.key(player, opponent, square, ply, shallow_scores),
+                square,
+            ));
+            moves &= moves - 1;
+        }
+
+        // stable, so that equal keys keep the bit index order
+        keyed.sort_by_key(|&(key, _)| std::cmp::Reverse(key));
+        squares.extend(keyed.into_iter().map(|(_, square)| square));
+
+        squares
+    }
+
+    fn key(
+        &self,
+        player: u64,
+        opponent: u64,
+        square: usize,
+        ply: usize,
+        shallow_scores: Option<&[i32; 64]>,
+    ) -> i64 {
+        let mut key = 0;
+
+        if let Some(scores) = shallow_scores {
+            key += i64::from(scores[square]) * SHALLOW_WEIGHT;
+        }
+
+        if self.options.killers {
+            let killers = &self.killers[ply.min(MAX_PLY - 1)];
+            if killers[0] == Some(square as u8) {
+                key += 2 * KILLER_WEIGHT;
+            } else if killers[1] == Some(square as u8) {
+                key += KILLER_WEIGHT;
+            }
+        }
+
+        if self.options.mobility {
+            let flipped = KoggeStone::flips_bits(player, opponent, square);
+            let next_player = opponent & !flipped;
+            let next_opponent = player | flipped | (1u64 << square);
+            let replies = KoggeStone::moves_bits(next_player, next_opponent).count_ones();
+            key -= i64::from(replies) * MOBILITY_WEIGHT;
+        }
+
+        if self.options.history {
+            key += i64::from(self.history[square] >> 4);
+        }
+
+        if self.options.square_priority {
+            key += i64::from(SQUARE_PRIORITY[square]) * SQUARE_WEIGHT;
+        }
+
+        key
+    }
+}
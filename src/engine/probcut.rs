@@ -0,0 +1,381 @@
+// Multi-ProbCut, as in Logistello.
+//
+// The score of a deep search is well predicted by the score of a shallow search of the same
+// position: deep = a * shallow + b, with a normal error of deviation sigma. Before searching a node
+// deeply, a shallow search tells whether the deep score is very likely outside the alpha-beta
+// window, in which case the node is cut. The regression parameters depend on the depth and on
+// the stage of the game, and are fitted on sample positions by the `Calibrator`.
+//
+// Parameters are saved in a text file, one line per stage and depth:
+//
+// # stage depth shallow a b sigma
+// 3 6 2 0.9731 0.1203 2.8410
+//
+// with scores in discs. Lines starting with `#` are comments.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use crate::board::position::Position;
+use crate::engine::eval::{stage, STAGES};
+use crate::engine::limits::SearchLimits;
+use crate::engine::search::Engine;
+
+/// Nodes with less remaining depth are never cut
+pub const MIN_DEPTH: u32 = 3;
+
+/// Deepest search with regression parameters
+pub const MAX_DEPTH: u32 = 16;
+
+/// Depth of the shallow search predicting a search at `depth`: about half of it, with the same
+/// parity because evaluations oscillate from one ply to the next, and at least 1
+///
+/// # Examples
+/// ```
+/// use othlib::engine::probcut::shallow_depth;
+///
+/// assert_eq!(shallow_depth(1), 1);
+/// assert_eq!(shallow_depth(3), 1);
+/// assert_eq!(shallow_depth(8), 4);
+/// assert_eq!(shallow_depth(9), 3);
+/// ```
+pub fn shallow_depth(depth: u32) -> u32 {
+    let mut shallow = depth / 2;
+    if (depth - shallow) % 2 == 1 {
+        shallow = shallow.saturating_sub(1);
+    }
+    shallow.max(1)
+}
+
+/// Errors found when reading or writing ProbCut parameters
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProbCutError {
+    Io(String),
+    /// a line is not made of stage, depth, shallow depth, a, b and sigma
+    Format(String),
+}
+
+impl fmt::Display for ProbCutError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProbCutError::Io(e) => write!(f, "I/O error: {}", e),
+            ProbCutError::Format(line) => write!(f, "invalid ProbCut parameters '{}'", line),
+        }
+    }
+}
+
+impl Error for ProbCutError {}
+
+impl From<std::io::Error> for ProbCutError {
+    fn from(e: std::io::Error) -> Self {
+        ProbCutError::Io(e.to_string())
+    }
+}
+
+/// Linear regression of a deep search score on a shallow search score
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Regression {
+    pub shallow: u32,
+    pub a: f32,
+    pub b: f32,
+    /// standard deviation of the prediction error
+    pub sigma: f32,
+}
+
+impl Regression {
+    /// Shallow score from which the deep score is above `beta` with the ProbCut confidence
+    pub fn upper_bound(&self, beta: f32, threshold: f32) -> f32 {
+        (beta + threshold * self.sigma - self.b) / self.a
+    }
+
+    /// Shallow score under which the deep score is below `alpha` with the ProbCut confidence
+    pub fn lower_bound(&self, alpha: f32, threshold: f32) -> f32 {
+        (alpha - threshold * self.sigma - self.b) / self.a
+    }
+}
+
+/// Regression parameters for each stage and depth, and the cut threshold
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProbCut {
+    /// number of standard deviations a shallow score must exceed a bound by to cut: higher is
+    /// safer and slower
+    pub threshold: f32,
+    regressions: Vec<Option<Regression>>,
+}
+
+impl Default for ProbCut {
+    fn default() -> Self {
+        ProbCut::new()
+    }
+}
+
+impl ProbCut {
+    /// No parameters, so nothing is ever cut
+    pub fn new() -> Self {
+        ProbCut {
+            threshold: 1.5,
+            regressions: vec![None; STAGES * (MAX_DEPTH as usize + 1)],
+        }
+    }
+
+    fn index(stage: usize, depth: u32) -> usize {
+        stage * (MAX_DEPTH as usize + 1) + depth as usize
+    }
+
+    /// Parameters to use at a node
+    pub fn regression(&self, stage: usize, depth: u32) -> Option<&Regression> {
+        if !(MIN_DEPTH..=MAX_DEPTH).contains(&depth) || stage >= STAGES {
+            return None;
+        }
+        self.regressions[ProbCut::index(stage, depth)].as_ref()
+    }
+
+    pub fn set_regression(&mut self, stage: usize, depth: u32, regression: Option<Regression>) {
+        self.regressions[ProbCut::index(stage, depth)] = regression;
+    }
+
+    /// Read parameters from a file
+    pub fn load(path: &str) -> Result<Self, ProbCutError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Write parameters to a file
+    pub fn save(&self, path: &str) -> Result<(), ProbCutError> {
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+}
+
+/// Read the text format
+///
+/// # Examples
+/// ```
+/// use othlib::engine::probcut::ProbCut;
+///
+/// let probcut: ProbCut = "# stage depth shallow a b sigma\n4 6 2 0.95 0.5 3.0".parse().unwrap();
+/// let regression = probcut.regression(4, 6).unwrap();
+/// assert_eq!((regression.shallow, regression.sigma), (2, 3.0));
+/// assert_eq!(probcut.regression(4, 7), None);
+///
+/// // round trip
+/// assert_eq!(probcut.to_string().parse::<ProbCut>().unwrap(), probcut);
+///
+/// assert!("4 6 2 0.95".parse::<ProbCut>().is_err());
+/// assert!("4 2 1 0.95 0.5 3.0".parse::<ProbCut>().is_err());
+/// ```
+impl std::str::FromStr for ProbCut {
+    type Err = ProbCutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut probcut = ProbCut::new();
+
+        for line in s.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = || ProbCutError::Format(line.to_string());
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() != 6 {
+                return Err(error());
+            }
+
+            let stage: usize = fields[0].parse().map_err(|_| error())?;
+            let depth: u32 = fields[1].parse().map_err(|_| error())?;
+            let shallow: u32 = fields[2].parse().map_err(|_| error())?;
+            let mut numbers = [0f32; 3];
+            for (n, field) in numbers.iter_mut().zip(&fields[3..]) {
+                *n = field.parse().map_err(|_| error())?;
+            }
+
+            if stage >= STAGES
+                || !(MIN_DEPTH..=MAX_DEPTH).contains(&depth)
+                || shallow >= depth
+                || numbers[0] <= 0.0
+            {
+                return Err(error());
+            }
+
+            probcut.set_regression(
+                stage,
+                depth,
+                Some(Regression {
+                    shallow,
+                    a: numbers[0],
+                    b: numbers[1],
+                    sigma: numbers[2],
+                }),
+            );
+        }
+
+        Ok(probcut)
+    }
+}
+
+impl fmt::Display for ProbCut {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "# stage depth shallow a b sigma")?;
+        for stage in 0..STAGES {
+            for depth in MIN_DEPTH..=MAX_DEPTH {
+                if let Some(r) = self.regression(stage, depth) {
+                    writeln!(
+                        f,
+                        "{} {} {} {} {} {}",
+                        stage, depth, r.shallow, r.a, r.b, r.sigma
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Collects shallow and deep search scores of sample positions, and fits the regressions
+pub struct Calibrator {
+    /// deepest search to calibrate
+    pub max_depth: u32,
+    /// minimum number of positions to fit the parameters of a stage and depth
+    pub min_samples: usize,
+    // (shallow score, deep score) for each stage and depth
+    samples: Vec<Vec<(f32, f32)>>,
+}
+
+impl Calibrator {
+    pub fn new(max_depth: u32) -> Self {
+        Calibrator {
+            max_depth: max_depth.min(MAX_DEPTH),
+            min_samples: 10,
+            samples: vec![Vec::new(); STAGES * (MAX_DEPTH as usize + 1)],
+        }
+    }
+
+    /// Number of score pairs collected
+    pub fn len(&self) -> usize {
+        self.samples.iter().map(Vec::len).sum()
+    }
+
+    /// True if no score was collected
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Search a position at all depths up to `max_depth`, without ProbCut. Returns false if the
+    /// position is too close to the end to be searched that deep.
+    pub fn add_position(&mut self, engine: &mut Engine, position: &Position) -> bool {
+        let empties = position.nb_empty();
+        if empties <= engine.endgame_empties.max(self.max_depth) || position.must_pass() {
+            return false;
+        }
+
+        let probcut = engine.probcut().cloned();
+        engine.set_probcut(None);
+
+        let scores: Vec<f32> = (0..=self.max_depth)
+            .map(|depth| {
+                if depth == 0 {
+                    0.0
+                } else {
+                    engine
+                        .search(position, &SearchLimits::depth(depth), false)
+                        .score
+                }
+            })
+            .collect();
+
+        engine.set_probcut(probcut);
+
+        let (player, opponent) = (position.player().bits, position.opponent().bits);
+        let stage = stage(player, opponent);
+        for depth in MIN_DEPTH..=self.max_depth {
+            let shallow = shallow_depth(depth);
+            self.samples[ProbCut::index(stage, depth)]
+                .push((scores[shallow as usize], scores[depth as usize]));
+        }
+
+        true
+    }
+
+    /// Fit a regression for each stage and depth having enough samples
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::probcut::Calibrator;
+    /// use othlib::engine::search::Engine;
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let game = GameRecord::from_move_string(
+    ///     "F5-D6-C3-D3-C4-F4-F6-F3-E6-E7-D7-G6-F8-F7-G5-H6-H4-G4-H3-H5-G3",
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut engine = Engine::new(Box::new(MobilityEvaluator));
+    /// let mut calibrator = Calibrator::new(4);
+    /// calibrator.min_samples = 3;
+    /// for ply in 0..=game.moves.len() {
+    ///     calibrator.add_position(&mut engine, &game.position_at(ply));
+    /// }
+    ///
+    /// let probcut = calibrator.fit();
+    /// let regression = probcut.regression(1, 4).unwrap();
+    /// assert_eq!(regression.shallow, 2);
+    /// assert!(regression.a > 0.0 && regression.sigma >= 0.0);
+    /// ```
+    pub fn fit(&self) -> ProbCut {
+        let mut probcut = ProbCut::new();
+
+        for stage in 0..STAGES {
+            for depth in MIN_DEPTH..=self.max_depth {
+                let samples = &self.samples[ProbCut::index(stage, depth)];
+                let regression =
+                    fit_line(samples, self.min_samples).map(|(a, b, sigma)| Regression {
+                        shallow: shallow_depth(depth),
+                        a,
+                        b,
+                        sigma,
+                    });
+                probcut.set_regression(stage, depth, regression);
+            }
+        }
+
+        probcut
+    }
+}
+
+// least squares fit of y = a * x + b, with the standard deviation of the residuals. Fits with a
+// non positive slope are rejected, since the bounds divide by it.
+fn fit_line(samples: &[(f32, f32)], min_samples: usize) -> Option<(f32, f32, f32)> {
+    if samples.is_empty() || samples.len() < min_samples {
+        return None;
+    }
+
+    let n = samples.len() as f64;
+    let mean_x = samples.iter().map(|&(x, _)| f64::from(x)).sum::<f64>() / n;
+    let mean_y = samples.iter().map(|&(_, y)| f64::from(y)).sum::<f64>() / n;
+
+    let (mut sxx, mut sxy) = (0.0, 0.0);
+    for &(x, y) in samples {
+        let (dx, dy) = (f64::from(x) - mean_x, f64::from(y) - mean_y);
+        sxx += dx * dx;
+        sxy += dx * dy;
+    }
+
+    // all shallow scores are equal: the deep score is predicted by its mean
+    let a = if sxx > 0.0 { sxy / sxx } else { 1.0 };
+    if a <= 0.0 {
+        return None;
+    }
+    let b = mean_y - a * mean_x;
+
+    let variance = samples
+        .iter()
+        .map(|&(x, y)| {
+            let error = f64::from(y) - (a * f64::from(x) + b);
+            error * error
+        })
+        .sum::<f64>()
+        / n;
+
+    Some((a as f32, b as f32, variance.sqrt() as f32))
+}
@@ -0,0 +1,917 @@
+// Game tree search: iterative deepening alpha-beta on the evaluation in the middle game, and the
+// exact endgame solver close to the end.
+//
+// Internally, midgame scores are hundredths of discs for the color to move, so that exact final
+// scores and evaluations compare with each other.
+//
+// With several threads, the midgame search is a Lazy SMP as in the endgame solver: helper threads
+// run their own iterative deepening on the same position, half of them one ply ahead and all with
+// their root moves in another order, and share what they find through the transposition table.
+// The main thread alone follows the limits, its result is returned, and it stops the helpers.
+// A single thread gives reproducible results.
+
+use std::mem;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crate::board::movegen::KoggeStone;
+use crate::board::position::{Move, Position};
+use crate::engine::endgame::EndgameSolver;
+use crate::engine::eval::{stage, Evaluator};
+use crate::engine::limits::{SearchControl, SearchLimits, TimeManager};
+use crate::engine::ordering::{MoveOrderer, OrderingOptions};
+use crate::engine::probcut::{ProbCut, MIN_DEPTH};
+use crate::engine::table::{TableEntry, TranspositionTable};
+
+// hundredths of discs
+const SCALE: i32 = 100;
+
+// larger than any score
+const INFINITY: i32 = 65 * SCALE;
+
+// midgame transposition table size as a power of 2
+const TT_BITS: u32 = 18;
+
+// by default, positions with this number of empties are solved exactly
+const ENDGAME_EMPTIES: u32 = 14;
+
+/// Outcome of a search
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    /// best move found, or a pass if there's no legal move
+    pub best_move: Move,
+    /// estimated disc differential for the color to move, exact if `exact` is true
+    pub score: f32,
+    /// true if the score was proven by searching to the end of the game
+    pub exact: bool,
+    /// depth of the last completed iteration, or the number of empties for exact scores
+    pub depth: u32,
+    /// positions searched
+    pub nodes: u64,
+    /// time spent
+    pub time: Duration,
+    /// expected moves from the position, the best move first
+    pub pv: Vec<Move>,
+}
+
+// final score when no one can play
+fn final_score(player: u64, opponent: u64) -> i32 {
+    let p = player.count_ones() as i32;
+    let o = opponent.count_ones() as i32;
+    let empty = 64 - p - o;
+
+    let score = match p.cmp(&o) {
+        std::cmp::Ordering::Greater => p - o + empty,
+        std::cmp::Ordering::Less => p - o - empty,
+        std::cmp::Ordering::Equal => 0,
+    };
+    score * SCALE
+}
+
+/// A game engine: evaluation, transposition table and endgame solver
+pub struct Engine {
+    evaluator: Box<dyn Evaluator + Send>,
+    solver: EndgameSolver,
+    table: TranspositionTable,
+    control: Arc<SearchControl>,
+    orderer: MoveOrderer,
+    probcut: Option<ProbCut>,
+    threads: usize,
+    /// positions with at most this number of empty squares are solved exactly, unless the
+    /// search depth is limited to fewer plies
+    pub endgame_empties: u32,
+}
+
+// midgame search state of one thread
+struct Worker<'a> {
+    evaluator: &'a dyn Evaluator,
+    table: &'a TranspositionTable,
+    probcut: Option<&'a ProbCut>,
+    orderer: MoveOrderer,
+    // 0 for the main thread
+    id: usize,
+    nodes: u64,
+    node_limit: u64,
+    aborted: bool,
+}
+
+impl Engine {
+    pub fn new(evaluator: Box<dyn Evaluator + Send>) -> Self {
+        Engine {
+            evaluator,
+            solver: EndgameSolver::new(),
+            table: TranspositionTable::new(TT_BITS),
+            control: Arc::new(SearchControl::new()),
+            orderer: MoveOrderer::default(),
+            probcut: None,
+            threads: 1,
+            endgame_empties: ENDGAME_EMPTIES,
+        }
+    }
+
+    /// Flags to stop the search or to end pondering from another thread
+    ///
+    /// # Examples
+    /// ```
+    /// use std::thread;
+    /// use std::time::Duration;
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::eval::PatternEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::search::Engine;
+    ///
+    /// let mut engine = Engine::new(Box::new(PatternEvaluator::zero()));
+    /// let control = engine.control();
+    ///
+    /// let search = thread::spawn(move || engine.search(&Position::new(), &SearchLimits::infinite(), false));
+    /// thread::sleep(Duration::from_millis(100));
+    /// control.stop();
+    ///
+    /// let result = search.join().unwrap();
+    /// assert!(Position::new().is_legal(result.best_move));
+    /// ```
+    pub fn control(&self) -> Arc<SearchControl> {
+        Arc::clone(&self.control)
+    }
+
+    /// Number of search threads
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Change the number of threads of the midgame search and of the endgame solver (at least
+    /// one). A single thread gives reproducible results, more threads search deeper in the same
+    /// time but results change from one run to another.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::search::Engine;
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let position = GameRecord::from_move_string("F5-D6-C3-D3-C4-F4-F6-F3-E6-E7")
+    ///     .unwrap()
+    ///     .final_position();
+    /// let mut engine = Engine::new(Box::new(MobilityEvaluator));
+    ///
+    /// engine.set_threads(4);
+    /// let parallel = engine.search(&position, &SearchLimits::depth(6), false);
+    /// assert_eq!(parallel.depth, 6);
+    /// assert!(position.is_legal(parallel.best_move));
+    ///
+    /// // single threaded searches are deterministic
+    /// engine.set_threads(1);
+    /// engine.clear();
+    /// let first = engine.search(&position, &SearchLimits::depth(6), false);
+    /// engine.clear();
+    /// let second = engine.search(&position, &SearchLimits::depth(6), false);
+    /// assert_eq!(first.nodes, second.nodes);
+    /// assert_eq!((first.best_move, first.score), (second.best_move, second.score));
+    /// assert_eq!(first.pv, second.pv);
+    /// ```
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+        self.solver.set_threads(threads);
+    }
+
+    /// Forget everything learnt in previous searches
+    pub fn clear(&mut self) {
+        self.table.clear();
+        self.solver.clear();
+        self.orderer.clear();
+    }
+
+    /// Heuristics used to sort moves
+    pub fn ordering(&self) -> OrderingOptions {
+        self.orderer.options
+    }
+
+    /// Choose the move ordering heuristics, for instance to compare node counts
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::ordering::OrderingOptions;
+    /// use othlib::engine::search::Engine;
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let position = GameRecord::from_move_string("F5-D6-C3-D3-C4-F4-F6-F3-E6-E7")
+    ///     .unwrap()
+    ///     .final_position();
+    /// let mut engine = Engine::new(Box::new(MobilityEvaluator));
+    ///
+    /// let mut nodes = Vec::new();
+    /// for options in &[OrderingOptions::none(), OrderingOptions::all()] {
+    ///     engine.clear();
+    ///     engine.set_ordering(*options);
+    ///     nodes.push(engine.search(&position, &SearchLimits::depth(6), false).nodes);
+    /// }
+    /// assert!(nodes[1] < nodes[0]);
+    /// ```
+    pub fn set_ordering(&mut self, options: OrderingOptions) {
+        self.orderer.options = options;
+    }
+
+    /// Multi-ProbCut parameters, if selective search is enabled
+    pub fn probcut(&self) -> Option<&ProbCut> {
+        self.probcut.as_ref()
+    }
+
+    /// Enable selective search with calibrated parameters, or disable it with `None`
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::{MobilityEvaluator, STAGES};
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::probcut::{shallow_depth, ProbCut, Regression};
+    /// use othlib::engine::search::Engine;
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let position = GameRecord::from_move_string("F5-D6-C3-D3-C4-F4-F6-F3-E6-E7")
+    ///     .unwrap()
+    ///     .final_position();
+    /// let mut engine = Engine::new(Box::new(MobilityEvaluator));
+    /// let full = engine.search(&position, &SearchLimits::depth(7), false);
+    ///
+    /// // deep scores predicted by shallow ones within 2 discs
+    /// let mut probcut = ProbCut::new();
+    /// for stage in 0..STAGES {
+    ///     for depth in 3..=7 {
+    ///         let regression = Regression { shallow: shallow_depth(depth), a: 1.0, b: 0.0, sigma: 2.0 };
+    ///         probcut.set_regression(stage, depth, Some(regression));
+    ///     }
+    /// }
+    ///
+    /// engine.clear();
+    /// engine.set_probcut(Some(probcut));
+    /// let selective = engine.search(&position, &SearchLimits::depth(7), false);
+    /// assert!(selective.nodes < full.nodes);
+    /// ```
+    pub fn set_probcut(&mut self, probcut: Option<ProbCut>) {
+        self.probcut = probcut;
+    }
+
+    /// Search the best move of a position within limits. Stop and ponder flags are cleared
+    /// when the search starts, unless the `ponder` argument is true.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::color::Color;
+    /// use othlib::board::position::Position;
+    /// use othlib::engine::eval::PatternEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::search::Engine;
+    ///
+    /// let mut engine = Engine::new(Box::new(PatternEvaluator::zero()));
+    /// let start = Position::new();
+    ///
+    /// let result = engine.search(&start, &SearchLimits::depth(4), false);
+    /// assert_eq!(result.depth, 4);
+    /// assert!(start.is_legal(result.best_move));
+    /// assert!(!result.exact);
+    ///
+    /// // node limits are deterministic
+    /// engine.clear();
+    /// let first = engine.search(&start, &SearchLimits::nodes(5000), false);
+    /// engine.clear();
+    /// let second = engine.search(&start, &SearchLimits::nodes(5000), false);
+    /// assert_eq!((first.best_move, first.nodes), (second.best_move, second.nodes));
+    ///
+    /// // a finished game has its final score
+    /// let squares = "XXXXXXXX".repeat(7) + "OOOOOOO-";
+    /// let end = Position::from_squares(&squares, Color::White).unwrap();
+    /// let result = engine.search(&end, &SearchLimits::depth(4), false);
+    /// assert!(result.exact);
+    /// assert_eq!(result.score, -50.0);
+    ///
+    /// // the endgame solver gives up at the limits, and the engine still moves
+    /// let squares = "--XXXXX- -OOOOOO- XOXXOOOX XXOXOXOX XOOXXOXX XOOOXXXX XXOOOXXO -OOOO---";
+    /// let late = Position::from_squares(squares, Color::Black).unwrap();
+    /// let result = engine.search(&late, &SearchLimits::nodes(1), false);
+    /// assert!(!result.exact);
+    /// assert!(late.is_legal(result.best_move));
+    ///
+    /// let solved = engine.search(&late, &SearchLimits::infinite(), false);
+    /// assert!(solved.exact);
+    /// assert!(solved.pv.len() > 1);
+    ///
+    /// // a depth limit shallower than the end of the game is kept
+    /// let shallow = engine.search(&late, &SearchLimits::depth(2), false);
+    /// assert_eq!(shallow.depth, 2);
+    /// assert!(!shallow.exact);
+    /// ```
+    pub fn search(
+        &mut self,
+        position: &Position,
+        limits: &SearchLimits,
+        ponder: bool,
+    ) -> SearchResult {
+        let control = Arc::clone(&self.control);
+        control.reset();
+        if ponder {
+            control.start_pondering();
+        }
+
+        let player = position.player().bits;
+        let opponent = position.opponent().bits;
+        let empties = position.nb_empty();
+        let mut time_manager = TimeManager::new(limits, empties, &control);
+
+        // no choice
+        let moves = KoggeStone::moves_bits(player, opponent);
+        if moves == 0 {
+            let (score, exact) = if position.is_game_over() {
+                ((final_score(player, opponent) / SCALE) as f32, true)
+            } else {
+                (self.evaluator.evaluate(player, opponent).round(), false)
+            };
+            return SearchResult {
+                best_move: Move::Pass,
+                score,
+                exact,
+                depth: 0,
+                nodes: 0,
+                time: time_manager.elapsed(),
+                pv: vec![Move::Pass],
+            };
+        }
+
+        // close to the end, solve exactly unless the depth is limited to fewer plies, or search
+        // as in the middle game if the solve is stopped by a limit
+        let mut solver_nodes = 0;
+        if limits
+            .depth
+            .map_or(empties <= self.endgame_empties, |d| d >= empties)
+        {
+            let solved = self.solver.solve_until(position, |nodes| {
+                solver_nodes = nodes;
+                time_manager.must_stop(nodes)
+            });
+
+            if let Some(solved) = solved {
+                return SearchResult {
+                    best_move: solved.best_move,
+                    score: solved.score as f32,
+                    exact: true,
+                    depth: empties,
+                    nodes: solved.nodes,
+                    time: time_manager.elapsed(),
+                    pv: self.solver.principal_variation(position, solved.score),
+                };
+            }
+        }
+
+        // iterative deepening, keeping the result of the last complete iteration. If none
+        // completes, the hash move or the best ordered one is played.
+        let max_depth = limits.depth.map_or(empties, |depth| depth.min(empties));
+        let threads = self.threads;
+        let helpers_control = SearchControl::new();
+        let (evaluator, table, probcut) = (&*self.evaluator, &self.table, self.probcut.as_ref());
+        let mut main = Worker::new(evaluator, table, probcut, mem::take(&mut self.orderer), 0);
+        main.nodes = solver_nodes;
+        main.node_limit = limits.nodes.unwrap_or(u64::MAX);
+
+        let first = main.ordered_moves(player, opponent, moves, 1, 0, &mut time_manager)[0];
+        let mut result = SearchResult {
+            best_move: Move::Play(first),
+            score: 0.0,
+            exact: false,
+            depth: 0,
+            nodes: 0,
+            time: Duration::default(),
+            pv: Vec::new(),
+        };
+
+        let helper_nodes: u64 = thread::scope(|scope| {
+            let helpers: Vec<_> = (1..threads)
+                .map(|id| {
+                    let (helpers_control, orderer) = (&helpers_control, main.orderer.clone());
+                    scope.spawn(move || {
+                        let mut time_manager =
+                            TimeManager::new(&SearchLimits::infinite(), empties, helpers_control);
+                        let mut helper = Worker::new(evaluator, table, probcut, orderer, id);
+                        let mut depth = 1 + id as u32 % 2;
+                        while depth <= max_depth {
+                            helper.root(player, opponent, depth, &mut time_manager);
+                            if helper.aborted {
+                                break;
+                            }
+                            depth += 1;
+                        }
+                        helper.nodes
+                    })
+                })
+                .collect();
+
+            let mut depth = 1;
+            while depth <= empties && time_manager.can_start_iteration(depth, main.nodes) {
+                let (score, best) = main.root(player, opponent, depth, &mut time_manager);
+                if main.aborted {
+                    break;
+                }
+
+                result.best_move = Move::Play(best);
+                result.score = score as f32 / SCALE as f32;
+                result.depth = depth;
+                depth += 1;
+            }
+
+            helpers_control.stop();
+            helpers.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        result.nodes = main.nodes + helper_nodes;
+        self.orderer = main.orderer;
+        result.time = time_manager.elapsed();
+        result.pv = self.principal_variation(position, result.best_move, result.depth);
+        result
+    }
+
+    /// Search every legal move, and return their results sorted from best to worst. Each result
+    /// has the root move as best move, and its score for the color to move at the root.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::{Move, Position};
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::search::Engine;
+    ///
+    /// let mut engine = Engine::new(Box::new(MobilityEvaluator));
+    /// let results = engine.search_moves(&Position::new(), &SearchLimits::depth(3));
+    ///
+    /// // all first moves are symmetric
+    /// assert_eq!(results.len(), 4);
+    /// assert!(results.iter().all(|r| r.score == results[0].score));
+    /// assert_eq!(results[0].pv[0], results[0].best_move);
+    /// ```
+    pub fn search_moves(
+        &mut self,
+        position: &Position,
+        limits: &SearchLimits,
+    ) -> Vec<SearchResult> {
+        let child_limits = SearchLimits {
+            depth: limits.depth.map(|depth| depth.saturating_sub(1).max(1)),
+            ..*limits
+        };
+
+        let mut moves = position.legal_moves().bits;
+        let mut results = Vec::new();
+        while moves != 0 {
+            let mv = Move::Play(moves.trailing_zeros() as usize);
+            moves &= moves - 1;
+
+            let mut child = *position;
+            child.play(mv).unwrap();
+            let mut pv = vec![mv];
+
+            // the score is seen from the opponent, unless the opponent has to pass
+            let mut sign = -1.0;
+            if child.must_pass() && !child.is_game_over() {
+                child.play(Move::Pass).unwrap();
+                pv.push(Move::Pass);
+                sign = 1.0;
+            }
+
+            let mut result = if child.is_game_over() {
+                let (player, opponent) = (child.player().bits, child.opponent().bits);
+                SearchResult {
+                    best_move: Move::Pass,
+                    score: (final_score(player, opponent) / SCALE) as f32,
+                    exact: true,
+                    depth: 0,
+                    nodes: 1,
+                    time: Duration::default(),
+                    pv: Vec::new(),
+                }
+            } else {
+                self.search(&child, &child_limits, false)
+            };
+
+            pv.append(&mut result.pv);
+            results.push(SearchResult {
+                best_move: mv,
+                score: sign * result.score,
+                depth: result.depth + 1,
+                pv,
+                ..result
+            });
+        }
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
+        results
+    }
+
+    // play the best move, then follow best moves stored in the table, which helper threads may
+    // have changed at the root
+    fn principal_variation(&self, position: &Position, best_move: Move, depth: u32) -> Vec<Move> {
+        let mut pv = vec![best_move];
+        let mut position = *position;
+        position.play(best_move).unwrap();
+
+        while pv.len() < depth as usize && !position.is_game_over() {
+            let mv = if position.must_pass() {
+                Move::Pass
+            } else {
+                let (player, opponent) = (position.player().bits, position.opponent().bits);
+                match self.table.probe(player, opponent) {
+                    Some(entry) => Move::Play(usize::from(entry.best)),
+                    None => break,
+                }
+            };
+
+            if position.play(mv).is_err() {
+                break;
+            }
+            pv.push(mv);
+        }
+
+        pv
+    }
+}
+
+impl<'a> Worker<'a> {
+    fn new(
+        evaluator: &'a dyn Evaluator,
+        table: &'a TranspositionTable,
+        probcut: Option<&'a ProbCut>,
+        orderer: MoveOrderer,
+        id: usize,
+    ) -> Self {
+        Worker {
+            evaluator,
+            table,
+            probcut,
+            orderer,
+            id,
+            nodes: 0,
+            node_limit: u64::MAX,
+            aborted: false,
+        }
+    }
+
+    // check limits from time to time
+    #[inline]
+    fn check_abort(&mut self, time_manager: &mut TimeManager) -> bool {
+        if !self.aborted
+            && (self.nodes >= self.node_limit
+                || (self.nodes & 1023 == 0 && time_manager.must_stop(self.nodes)))
+        {
+            self.aborted = true;
+        }
+        self.aborted
+    }
+
+    // moves to search, best first
+    fn ordered_moves(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        moves: u64,
+        depth: u32,
+        ply: usize,
+        time_manager: &mut TimeManager,
+    ) -> Vec<usize> {
+        let hash_move = self
+            .table
+            .probe(player, opponent)
+            .map(|entry| usize::from(entry.best));
+
+        // score each move with a shallow search
+        let options = self.orderer.options;
+        let shallow_scores = if options.use_shallow_search(depth) && moves.count_ones() > 1 {
+            let mut scores = [0; 64];
+            let mut remaining = moves;
+            while remaining != 0 {
+                let square = remaining.trailing_zeros() as usize;
+                let flipped = KoggeStone::flips_bits(player, opponent, square);
+                scores[square] = -self.negamax(
+                    opponent & !flipped,
+                    player | flipped | (1u64 << square),
+                    options.shallow_depth - 1,
+                    -INFINITY,
+                    INFINITY,
+                    false,
+                    ply + 1,
+                    time_manager,
+                );
+                remaining &= remaining - 1;
+            }
+            Some(scores)
+        } else {
+            None
+        };
+
+        self.orderer.order(
+            player,
+            opponent,
+            moves,
+            hash_move,
+            ply,
+            shallow_scores.as_ref(),
+        )
+    }
+
+    fn root(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        depth: u32,
+        time_manager: &mut TimeManager,
+    ) -> (i32, usize) {
+        let moves = KoggeStone::moves_bits(player, opponent);
+        let mut alpha = -INFINITY;
+        let beta = INFINITY;
+        let mut best_square = 0;
+
+        // helpers start with another move, to fill the table with other subtrees
+        let mut squares = self.ordered_moves(player, opponent, moves, depth, 0, time_manager);
+        let len = squares.len();
+        squares.rotate_left(self.id % len);
+
+        for square in squares {
+            let flipped = KoggeStone::flips_bits(player, opponent, square);
+            let next_player = opponent & !flipped;
+            let next_opponent = player | flipped | (1u64 << square);
+
+            let score = if alpha == -INFINITY {
+                -self.negamax(
+                    next_player,
+                    next_opponent,
+                    depth - 1,
+                    -beta,
+                    -alpha,
+                    false,
+                    1,
+                    time_manager,
+                )
+            } else {
+                let mut score = -self.negamax(
+                    next_player,
+                    next_opponent,
+                    depth - 1,
+                    -alpha - 1,
+                    -alpha,
+                    false,
+                    1,
+                    time_manager,
+                );
+                if score > alpha && !self.aborted {
+                    score = -self.negamax(
+                        next_player,
+                        next_opponent,
+                        depth - 1,
+                        -beta,
+                        -alpha,
+                        false,
+                        1,
+                        time_manager,
+                    );
+                }
+                score
+            };
+            if self.aborted {
+                break;
+            }
+
+            if score > alpha {
+                alpha = score;
+                best_square = square;
+            }
+        }
+
+        if !self.aborted {
+            self.store(player, opponent, depth, alpha, alpha, best_square);
+        }
+        (alpha, best_square)
+    }
+
+    fn store(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        depth: u32,
+        lower: i32,
+        upper: i32,
+        best: usize,
+    ) {
+        self.table.store(
+            player,
+            opponent,
+            TableEntry {
+                lower: lower as i16,
+                upper: upper as i16,
+                depth: depth as u8,
+                best: best as u8,
+            },
+        );
+    }
+
+    // Multi-ProbCut: a shallow search shows that the score is very likely out of the window
+    #[allow(clippy::too_many_arguments)]
+    fn probcut_cut(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        depth: u32,
+        alpha: i32,
+        beta: i32,
+        ply: usize,
+        time_manager: &mut TimeManager,
+    ) -> Option<i32> {
+        let probcut = self.probcut?;
+        let threshold = probcut.threshold;
+        let regression = *probcut.regression(stage(player, opponent), depth)?;
+        let to_score = |discs: f32| (discs * SCALE as f32).round() as i32;
+        let to_discs = |score: i32| score as f32 / SCALE as f32;
+
+        if beta < INFINITY {
+            let bound = to_score(regression.upper_bound(to_discs(beta), threshold));
+            if bound < INFINITY {
+                let score = self.negamax(
+                    player,
+                    opponent,
+                    regression.shallow,
+                    bound - 1,
+                    bound,
+                    false,
+                    ply,
+                    time_manager,
+                );
+                if self.aborted {
+                    return Some(0);
+                }
+                if score >= bound {
+                    return Some(beta);
+                }
+            }
+        }
+
+        if alpha > -INFINITY {
+            let bound = to_score(regression.lower_bound(to_discs(alpha), threshold));
+            if bound > -INFINITY {
+                let score = self.negamax(
+                    player,
+                    opponent,
+                    regression.shallow,
+                    bound,
+                    bound + 1,
+                    false,
+                    ply,
+                    time_manager,
+                );
+                if self.aborted {
+                    return Some(0);
+                }
+                if score <= bound {
+                    return Some(alpha);
+                }
+            }
+        }
+
+        None
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn negamax(
+        &mut self,
+        player: u64,
+        opponent: u64,
+        depth: u32,
+        mut alpha: i32,
+        mut beta: i32,
+        passed: bool,
+        ply: usize,
+        time_manager: &mut TimeManager,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.check_abort(time_manager) {
+            return 0;
+        }
+
+        let moves = KoggeStone::moves_bits(player, opponent);
+        if moves == 0 {
+            if passed {
+                return final_score(player, opponent);
+            }
+            return -self.negamax(
+                opponent,
+                player,
+                depth,
+                -beta,
+                -alpha,
+                true,
+                ply + 1,
+                time_manager,
+            );
+        }
+
+        if depth == 0 {
+            return (self.evaluator.evaluate(player, opponent) * SCALE as f32).round() as i32;
+        }
+
+        // transposition table cut
+        if let Some(entry) = self.table.probe(player, opponent) {
+            if u32::from(entry.depth) >= depth {
+                let (lower, upper) = (i32::from(entry.lower), i32::from(entry.upper));
+                if lower >= beta {
+                    return lower;
+                }
+                if upper <= alpha {
+                    return upper;
+                }
+                if lower == upper {
+                    return lower;
+                }
+                alpha = alpha.max(lower);
+                beta = beta.min(upper);
+            }
+        }
+
+        if depth >= MIN_DEPTH {
+            if let Some(score) =
+                self.probcut_cut(player, opponent, depth, alpha, beta, ply, time_manager)
+            {
+                return score;
+            }
+        }
+
+        let original_alpha = alpha;
+        let mut best_score = -INFINITY;
+        let mut best_square = 0;
+
+        for square in self.ordered_moves(player, opponent, moves, depth, ply, time_manager) {
+            let flipped = KoggeStone::flips_bits(player, opponent, square);
+            let next_player = opponent & !flipped;
+            let next_opponent = player | flipped | (1u64 << square);
+
+            let score = if best_score == -INFINITY {
+                -self.negamax(
+                    next_player,
+                    next_opponent,
+                    depth - 1,
+                    -beta,
+                    -alpha,
+                    false,
+                    ply + 1,
+                    time_manager,
+                )
+            } else {
+                let mut score = -self.negamax(
+                    next_player,
+                    next_opponent,
+                    depth - 1,
+                    -alpha - 1,
+                    -alpha,
+                    false,
+                    ply + 1,
+                    time_manager,
+                );
+                if score > alpha && score < beta && !self.aborted {
+                    score = -self.negamax(
+                        next_player,
+                        next_opponent,
+                        depth - 1,
+                        -beta,
+                        -score,
+                        false,
+                        ply + 1,
+                        time_manager,
+                    );
+                }
+                score
+            };
+            if self.aborted {
+                return 0;
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_square = square;
+                if score > alpha {
+                    alpha = score;
+                }
+                if alpha >= beta {
+                    self.orderer.record_cutoff(ply, square, depth);
+                    break;
+                }
+            }
+        }
+
+        let (lower, upper) = if best_score <= original_alpha {
+            (-INFINITY, best_score)
+        } else if best_score >= beta {
+            (best_score, INFINITY)
+        } else {
+            (best_score, best_score)
+        };
+        self.store(player, opponent, depth, lower, upper, best_square);
+
+        best_score
+    }
+}
@@ -0,0 +1,119 @@
+// Transposition table shared by search threads, without locks.
+//
+// Each slot holds 3 atomic words: the data, and both bitboards xored with the data. A slot
+// written by 2 threads at the same time mixes words of both writes, and then fails the key check
+// when read, so a torn entry is only a missed hit and never a wrong one.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// What is known about a position: the score is in `lower..=upper` for a search `depth` plies
+/// deep, and `best` is the bit index of the best move found. Scores are in the unit of the
+/// search using the table: discs for the endgame solver, hundredths of discs in the middle game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableEntry {
+    pub lower: i16,
+    pub upper: i16,
+    pub depth: u8,
+    pub best: u8,
+}
+
+impl TableEntry {
+    // the bit 48 tells a cleared slot from an entry
+    fn pack(&self) -> u64 {
+        u64::from(self.lower as u16)
+            | u64::from(self.upper as u16) << 16
+            | u64::from(self.depth) << 32
+            | u64::from(self.best) << 40
+            | 1 << 48
+    }
+
+    fn unpack(data: u64) -> TableEntry {
+        TableEntry {
+            lower: data as u16 as i16,
+            upper: (data >> 16) as u16 as i16,
+            depth: (data >> 32) as u8,
+            best: (data >> 40) as u8,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Slot {
+    player: AtomicU64,
+    opponent: AtomicU64,
+    data: AtomicU64,
+}
+
+/// A fixed size, always replace, transposition table which can be shared between threads
+pub struct TranspositionTable {
+    bits: u32,
+    slots: Vec<Slot>,
+}
+
+impl TranspositionTable {
+    /// Create a table of `2^bits` entries
+    pub fn new(bits: u32) -> Self {
+        TranspositionTable {
+            bits,
+            slots: (0..1usize << bits).map(|_| Slot::default()).collect(),
+        }
+    }
+
+    /// Forget all entries
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.player.store(0, Ordering::Relaxed);
+            slot.opponent.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+    }
+
+    #[inline]
+    fn slot(&self, player: u64, opponent: u64) -> &Slot {
+        let hash =
+            player.wrapping_mul(0x9E3779B97F4A7C15) ^ opponent.wrapping_mul(0xC2B2AE3D27D4EB4F);
+        &self.slots[(hash >> (64 - self.bits)) as usize]
+    }
+
+    /// Look for a position
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::table::{TableEntry, TranspositionTable};
+    ///
+    /// let table = TranspositionTable::new(10);
+    /// let entry = TableEntry { lower: -400, upper: 1200, depth: 8, best: 26 };
+    ///
+    /// table.store(0x0810, 0x1008, entry);
+    /// assert_eq!(table.probe(0x0810, 0x1008), Some(entry));
+    /// assert_eq!(table.probe(0x1008, 0x0810), None);
+    ///
+    /// table.clear();
+    /// assert_eq!(table.probe(0x0810, 0x1008), None);
+    /// ```
+    #[inline]
+    pub fn probe(&self, player: u64, opponent: u64) -> Option<TableEntry> {
+        let slot = self.slot(player, opponent);
+        let data = slot.data.load(Ordering::Relaxed);
+
+        if slot.player.load(Ordering::Relaxed) ^ data == player
+            && slot.opponent.load(Ordering::Relaxed) ^ data == opponent
+            && data != 0
+        {
+            Some(TableEntry::unpack(data))
+        } else {
+            None
+        }
+    }
+
+    /// Store what is known about a position, replacing whatever was in its slot
+    #[inline]
+    pub fn store(&self, player: u64, opponent: u64, entry: TableEntry) {
+        let slot = self.slot(player, opponent);
+        let data = entry.pack();
+
+        slot.player.store(player ^ data, Ordering::Relaxed);
+        slot.opponent.store(opponent ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+}
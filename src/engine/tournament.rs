@@ -0,0 +1,369 @@
+// Engine-vs-engine matches: games between two engine configurations, and the statistics telling
+// whether one of them is stronger.
+//
+// Results are seen from the first engine. The Elo difference comes from the mean score, with a
+// 95% confidence interval from the variance of the game results. The sequential probability ratio
+// test (SPRT) stops a match as soon as one of two hypotheses, "the difference is elo0" and "the
+// difference is elo1", is likely enough, using the normal approximation of the log-likelihood
+// ratio.
+
+use std::fmt;
+
+use crate::board::color::Color;
+use crate::board::position::Move;
+use crate::engine::limits::SearchLimits;
+use crate::engine::search::Engine;
+use crate::game::record::{GameRecord, RecordError, RecordedMove};
+
+// quantile of the normal distribution for a 95% confidence interval
+const Z_95: f64 = 1.959_964;
+
+/// An engine with its name and search limits
+pub struct Player {
+    pub name: String,
+    pub engine: Engine,
+    pub limits: SearchLimits,
+}
+
+impl Player {
+    pub fn new(name: &str, engine: Engine, limits: SearchLimits) -> Self {
+        Player {
+            name: name.to_string(),
+            engine,
+            limits,
+        }
+    }
+}
+
+/// Play a game from the end of an opening until it's over. Engines forget their previous
+/// searches first, so that a game only depends on its opening. Fails if the opening or an
+/// engine move is illegal.
+///
+/// # Examples
+/// ```
+/// use othlib::engine::eval::MobilityEvaluator;
+/// use othlib::engine::limits::SearchLimits;
+/// use othlib::engine::search::Engine;
+/// use othlib::engine::tournament::{play_game, Player};
+/// use othlib::game::record::GameRecord;
+///
+/// let mut a = Player::new("a", Engine::new(Box::new(MobilityEvaluator)), SearchLimits::depth(1));
+/// let mut b = Player::new("b", Engine::new(Box::new(MobilityEvaluator)), SearchLimits::depth(2));
+///
+/// let opening = GameRecord::from_move_string("f5d6").unwrap();
+/// let game = play_game(&mut a, &mut b, &opening).unwrap();
+/// assert!(game.final_position().is_game_over());
+/// assert_eq!(game.black_player, "a");
+/// assert_eq!(game.result, Some(game.final_position().final_score() as f32));
+/// ```
+pub fn play_game(
+    black: &mut Player,
+    white: &mut Player,
+    opening: &GameRecord,
+) -> Result<GameRecord, RecordError> {
+    opening.validate()?;
+    let mut game = opening.clone();
+    game.black_player = black.name.clone();
+    game.white_player = white.name.clone();
+    black.engine.clear();
+    white.engine.clear();
+
+    loop {
+        let position = game.final_position();
+        if position.is_game_over() {
+            break;
+        }
+
+        let rec = if position.must_pass() {
+            RecordedMove::new(Move::Pass)
+        } else {
+            let player = match position.side {
+                Color::Black => &mut *black,
+                Color::White => &mut *white,
+            };
+            let result = player.engine.search(&position, &player.limits, false);
+            let mut rec = RecordedMove::new(result.best_move);
+            rec.eval = Some(result.score);
+            rec.time = Some(result.time.as_secs_f32());
+            rec
+        };
+        game.push_move(rec)?;
+    }
+
+    game.result = Some(game.final_position().final_score() as f32);
+    Ok(game)
+}
+
+/// Results of a match, from the point of view of the first engine
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct MatchStats {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+    // sums of the disc differentials and of their squares
+    discs: i64,
+    discs_squared: i64,
+}
+
+impl MatchStats {
+    pub fn new() -> Self {
+        MatchStats::default()
+    }
+
+    /// Add the result of a game, as the disc differential of the first engine
+    pub fn add(&mut self, discs: i32) {
+        match discs {
+            d if d > 0 => self.wins += 1,
+            d if d < 0 => self.losses += 1,
+            _ => self.draws += 1,
+        }
+        self.discs += i64::from(discs);
+        self.discs_squared += i64::from(discs) * i64::from(discs);
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    /// Mean score per game, a draw being worth half a win
+    pub fn score(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+        (f64::from(self.wins) + f64::from(self.draws) / 2.0) / f64::from(self.games())
+    }
+
+    // variance of the result of a game
+    fn variance(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        let p = self.score();
+        let n = f64::from(self.games());
+        (f64::from(self.wins) * (1.0 - p).powi(2)
+            + f64::from(self.draws) * (0.5 - p).powi(2)
+            + f64::from(self.losses) * p.powi(2))
+            / n
+    }
+
+    // variance of the result of a game, with a win and a loss added when all results are the
+    // same and the variance would be 0
+    fn regularized_variance(&self) -> f64 {
+        let variance = self.variance();
+        if variance > 0.0 || self.games() == 0 {
+            return variance;
+        }
+        MatchStats {
+            wins: self.wins + 1,
+            losses: self.losses + 1,
+            ..*self
+        }
+        .variance()
+    }
+
+    /// Mean disc differential per game
+    pub fn mean_discs(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        self.discs as f64 / f64::from(self.games())
+    }
+
+    /// Standard deviation of the disc differential
+    pub fn discs_deviation(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        let mean = self.mean_discs();
+        (self.discs_squared as f64 / f64::from(self.games()) - mean * mean)
+            .max(0.0)
+            .sqrt()
+    }
+
+    /// Elo difference, with the half-width of its 95% confidence interval. When an engine won
+    /// every game, its score is taken as half a game short of 100%, so that the difference is a
+    /// finite lower bound. The interval is bounded the same way.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::tournament::MatchStats;
+    ///
+    /// let mut stats = MatchStats::new();
+    /// for discs in [10, -4, 0, 6, 2, -8, 12, 0] {
+    ///     stats.add(discs);
+    /// }
+    /// assert_eq!((stats.wins, stats.draws, stats.losses), (4, 2, 2));
+    /// assert_eq!(stats.score(), 0.625);
+    /// assert_eq!(stats.mean_discs(), 2.25);
+    ///
+    /// let (elo, margin) = stats.elo();
+    /// assert!((elo - 88.7).abs() < 0.1);
+    /// assert!(margin > elo);
+    ///
+    /// // 10 wins out of 10 games score at least 95%
+    /// let mut stats = MatchStats::new();
+    /// for _ in 0..10 {
+    ///     stats.add(2);
+    /// }
+    /// let (elo, margin) = stats.elo();
+    /// assert!((elo - 511.5).abs() < 0.1);
+    /// assert!(margin.is_finite() && margin > 0.0);
+    /// ```
+    pub fn elo(&self) -> (f64, f64) {
+        if self.games() == 0 {
+            return (0.0, 0.0);
+        }
+        let n = f64::from(self.games());
+        let clamp = |score: f64| score.clamp(0.5 / n, 1.0 - 0.5 / n);
+        let p = clamp(self.score());
+        let deviation = (self.regularized_variance() / n).sqrt();
+
+        let low = elo_difference(clamp(p - Z_95 * deviation));
+        let high = elo_difference(clamp(p + Z_95 * deviation));
+        (elo_difference(p), (high - low) / 2.0)
+    }
+}
+
+/// Elo difference giving a mean score
+///
+/// # Examples
+/// ```
+/// use othlib::engine::tournament::elo_difference;
+///
+/// assert_eq!(elo_difference(0.5), 0.0);
+/// assert!((elo_difference(0.75) - 190.8).abs() < 0.1);
+/// assert_eq!(elo_difference(1.0), f64::INFINITY);
+/// ```
+pub fn elo_difference(score: f64) -> f64 {
+    if score <= 0.0 {
+        f64::NEG_INFINITY
+    } else if score >= 1.0 {
+        f64::INFINITY
+    } else {
+        400.0 * (score / (1.0 - score)).log10()
+    }
+}
+
+// expected score of an Elo difference
+fn expected_score(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+/// State of a sequential probability ratio test
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtStatus {
+    /// more games are needed
+    Continue,
+    /// the difference is elo0
+    AcceptH0,
+    /// the difference is elo1
+    AcceptH1,
+}
+
+impl fmt::Display for SprtStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SprtStatus::Continue => write!(f, "continue"),
+            SprtStatus::AcceptH0 => write!(f, "H0 accepted"),
+            SprtStatus::AcceptH1 => write!(f, "H1 accepted"),
+        }
+    }
+}
+
+/// A sequential probability ratio test of H0 (the Elo difference is `elo0`) against H1 (it is
+/// `elo1`), with `alpha` and `beta` as false positive and false negative rates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    /// A test with 5% error rates
+    pub fn new(elo0: f64, elo1: f64) -> Self {
+        Sprt {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// Log-likelihood ratio of H1 against H0. When all games have the same result, the variance
+    /// is estimated with an extra win and loss.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::tournament::{MatchStats, Sprt, SprtStatus};
+    ///
+    /// let sprt = Sprt::new(0.0, 50.0);
+    /// assert_eq!(sprt.llr(&MatchStats::new()), 0.0);
+    ///
+    /// // only draws: the engines are equal
+    /// let mut stats = MatchStats::new();
+    /// while sprt.status(&stats) == SprtStatus::Continue {
+    ///     stats.add(0);
+    /// }
+    /// assert_eq!(sprt.status(&stats), SprtStatus::AcceptH0);
+    ///
+    /// // only wins
+    /// let mut stats = MatchStats::new();
+    /// while sprt.status(&stats) == SprtStatus::Continue {
+    ///     stats.add(10);
+    /// }
+    /// assert_eq!(sprt.status(&stats), SprtStatus::AcceptH1);
+    /// ```
+    pub fn llr(&self, stats: &MatchStats) -> f64 {
+        let variance = stats.regularized_variance();
+        if variance == 0.0 {
+            return 0.0;
+        }
+        let s0 = expected_score(self.elo0);
+        let s1 = expected_score(self.elo1);
+
+        f64::from(stats.games()) * (s1 - s0) * (2.0 * stats.score() - s0 - s1) / (2.0 * variance)
+    }
+
+    /// Lower and upper bounds of the log-likelihood ratio: H0 is accepted below the lower one
+    /// and H1 above the upper one
+    pub fn bounds(&self) -> (f64, f64) {
+        (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        )
+    }
+
+    /// Where the test stands after these results
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::tournament::{MatchStats, Sprt, SprtStatus};
+    ///
+    /// let sprt = Sprt::new(0.0, 50.0);
+    /// let mut stats = MatchStats::new();
+    /// assert_eq!(sprt.status(&stats), SprtStatus::Continue);
+    ///
+    /// // a clearly stronger engine
+    /// for _ in 0..10 {
+    ///     for discs in [8, 4, -2, 6] {
+    ///         stats.add(discs);
+    ///     }
+    /// }
+    /// assert!(sprt.llr(&stats) > sprt.bounds().1);
+    /// assert_eq!(sprt.status(&stats), SprtStatus::AcceptH1);
+    /// ```
+    pub fn status(&self, stats: &MatchStats) -> SprtStatus {
+        let llr = self.llr(stats);
+        let (lower, upper) = self.bounds();
+
+        if llr >= upper {
+            SprtStatus::AcceptH1
+        } else if llr <= lower {
+            SprtStatus::AcceptH0
+        } else {
+            SprtStatus::Continue
+        }
+    }
+}
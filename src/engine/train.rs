@@ -0,0 +1,220 @@
+// Fit pattern weights on game positions.
+//
+// Every position of a game is labelled with the game result, seen from the color to move. Each
+// stage is then fitted on its own positions by gradient descent on the squared error. The step
+// of a weight is divided by the number of positions using it, so that rare patterns don't
+// diverge while common ones still learn quickly.
+
+use crate::board::color::Color;
+use crate::board::position::Position;
+use crate::engine::eval::{features, stage, PatternEvaluator, FEATURES, STAGES, STAGE_SIZE};
+use crate::game::record::GameRecord;
+
+/// Which score of a game is used as the target of its positions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Label {
+    /// the disc differential at the end of the game
+    Result,
+    /// the disc differential with perfect play, as computed in WTHOR files
+    Theoretical,
+}
+
+impl Label {
+    /// Final disc differential for black, if the game has it
+    pub fn black_score(&self, game: &GameRecord) -> Option<f32> {
+        match self {
+            Label::Result => game.result,
+            Label::Theoretical => game
+                .theoretical_score
+                .map(|discs| 2.0 * f32::from(discs) - 64.0),
+        }
+    }
+}
+
+// a training position: its weights and its score for the color to move
+struct Sample {
+    features: [u32; FEATURES],
+    target: f32,
+}
+
+/// Collect positions, then fit the weights of an evaluator on them
+pub struct Trainer {
+    /// number of passes over the positions
+    pub epochs: usize,
+    /// size of the gradient steps, training diverges above about 0.03
+    pub rate: f32,
+    samples: Vec<Vec<Sample>>,
+}
+
+impl Default for Trainer {
+    fn default() -> Self {
+        Trainer::new()
+    }
+}
+
+impl Trainer {
+    pub fn new() -> Self {
+        Trainer {
+            epochs: 100,
+            rate: 0.02,
+            samples: (0..STAGES).map(|_| Vec::new()).collect(),
+        }
+    }
+
+    /// Number of positions collected
+    pub fn len(&self) -> usize {
+        self.samples.iter().map(Vec::len).sum()
+    }
+
+    /// True if no position was collected
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of positions collected for a stage
+    pub fn stage_len(&self, stage: usize) -> usize {
+        self.samples[stage].len()
+    }
+
+    /// Add a position whose final disc differential for black is known
+    pub fn add_position(&mut self, position: &Position, black_score: f32) {
+        let (player, opponent) = (position.player().bits, position.opponent().bits);
+
+        let mut sample = Sample {
+            features: [0; FEATURES],
+            target: match position.side {
+                Color::Black => black_score,
+                Color::White => -black_score,
+            },
+        };
+        for (f, &feature) in sample
+            .features
+            .iter_mut()
+            .zip(features(player, opponent).iter())
+        {
+            *f = feature as u32;
+        }
+
+        self.samples[stage(player, opponent)].push(sample);
+    }
+
+    /// Add all the positions of a game where a move is to be played. Returns the number of
+    /// positions added, 0 if the game has no label.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::train::{Label, Trainer};
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let mut game = GameRecord::from_move_string("F5-D6-C3-D3-C4").unwrap();
+    /// let mut trainer = Trainer::new();
+    ///
+    /// assert_eq!(trainer.add_game(&game, Label::Result), 0);
+    ///
+    /// game.result = Some(12.0);
+    /// assert_eq!(trainer.add_game(&game, Label::Result), 6);
+    /// assert_eq!(trainer.stage_len(0), 4);
+    /// ```
+    pub fn add_game(&mut self, game: &GameRecord, label: Label) -> usize {
+        let black_score = match label.black_score(game) {
+            Some(score) => score,
+            None => return 0,
+        };
+
+        let mut added = 0;
+        for ply in 0..=game.moves.len() {
+            let position = game.position_at(ply);
+            if position.legal_moves().bits != 0 {
+                self.add_position(&position, black_score);
+                added += 1;
+            }
+        }
+
+        added
+    }
+
+    /// Fit the weights of all stages, starting from the evaluator weights. Returns the root
+    /// mean square error of each stage, or 0 for stages without positions.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::{Evaluator, PatternEvaluator};
+    /// use othlib::engine::train::{Label, Trainer};
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let mut trainer = Trainer::new();
+    /// for (moves, result) in &[("F5-D6-C3-D3-C4", 10.0), ("F5-F6-E6-F4-E3", -6.0)] {
+    ///     let mut game = GameRecord::from_move_string(moves).unwrap();
+    ///     game.result = Some(*result);
+    ///     trainer.add_game(&game, Label::Result);
+    /// }
+    ///
+    /// let mut evaluator = PatternEvaluator::zero();
+    /// let errors = trainer.train(&mut evaluator);
+    ///
+    /// // both games start the same way, so the first positions can't be told apart
+    /// assert!(errors[0] > 5.0 && errors[0] < 6.0);
+    ///
+    /// // black is to move after 4 moves, and won the first game only
+    /// let won = GameRecord::from_move_string("F5-D6-C3-D3").unwrap().final_position();
+    /// let lost = GameRecord::from_move_string("F5-F6-E6-F4").unwrap().final_position();
+    /// assert!(evaluator.evaluate_position(&won) > 5.0);
+    /// assert!(evaluator.evaluate_position(&lost) < -3.0);
+    /// ```
+    pub fn train(&self, evaluator: &mut PatternEvaluator) -> Vec<f32> {
+        (0..STAGES)
+            .map(|stage| {
+                let bias = &mut evaluator.bias[stage];
+                let weights = &mut evaluator.weights[stage * STAGE_SIZE..(stage + 1) * STAGE_SIZE];
+                self.train_stage(&self.samples[stage], bias, weights)
+            })
+            .collect()
+    }
+
+    fn train_stage(&self, samples: &[Sample], bias: &mut f32, weights: &mut [f32]) -> f32 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        // how many times each weight is used
+        let mut counts = vec![0u32; STAGE_SIZE];
+        for sample in samples {
+            for &f in sample.features.iter() {
+                counts[f as usize] += 1;
+            }
+        }
+
+        let n = samples.len() as f32;
+        let mut gradient = vec![0f32; STAGE_SIZE];
+        let mut square_error = 0.0;
+
+        for _ in 0..self.epochs {
+            let mut bias_gradient = 0.0;
+            square_error = 0.0;
+
+            for sample in samples {
+                let predicted = sample
+                    .features
+                    .iter()
+                    .fold(*bias, |acc, &f| acc + weights[f as usize]);
+                let error = sample.target - predicted;
+
+                square_error += error * error;
+                bias_gradient += error;
+                for &f in sample.features.iter() {
+                    gradient[f as usize] += error;
+                }
+            }
+
+            *bias += self.rate * bias_gradient / n;
+            for ((weight, g), &count) in weights.iter_mut().zip(gradient.iter_mut()).zip(&counts) {
+                if count > 0 {
+                    *weight += self.rate * *g / count as f32;
+                    *g = 0.0;
+                }
+            }
+        }
+
+        (square_error / n).sqrt()
+    }
+}
@@ -0,0 +1,283 @@
+// Export game records as CSV (with a header, configurable delimiter and columns) or JSON Lines
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use crate::board::notation::{self, Case};
+use crate::board::position::Move;
+use crate::game::opening;
+use crate::game::record::GameRecord;
+
+/// Columns which can be exported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Year,
+    Tournament,
+    BlackPlayer,
+    WhitePlayer,
+    BlackScore,
+    TheoreticalScore,
+    Moves,
+    FinalPosition,
+    Opening,
+}
+
+/// Default columns, matching the historical `wthor2csv` output
+pub const DEFAULT_COLUMNS: [Column; 6] = [
+    Column::Tournament,
+    Column::BlackPlayer,
+    Column::WhitePlayer,
+    Column::BlackScore,
+    Column::TheoreticalScore,
+    Column::Moves,
+];
+
+impl Column {
+    /// Name used in the CSV header, as JSON key and to select the column on the command line
+    pub fn name(&self) -> &'static str {
+        match self {
+            Column::Year => "year",
+            Column::Tournament => "tournament",
+            Column::BlackPlayer => "black",
+            Column::WhitePlayer => "white",
+            Column::BlackScore => "black_score",
+            Column::TheoreticalScore => "theoretical_score",
+            Column::Moves => "moves",
+            Column::FinalPosition => "final_position",
+            Column::Opening => "opening",
+        }
+    }
+}
+
+/// Error returned for an unknown column name
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownColumn(pub String);
+
+impl fmt::Display for UnknownColumn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown column '{}'", self.0)
+    }
+}
+
+impl Error for UnknownColumn {}
+
+/// Parse a column from its name
+///
+/// # Examples
+/// ```
+/// use othlib::game::export::Column;
+///
+/// assert_eq!("black_score".parse::<Column>(), Ok(Column::BlackScore));
+/// assert!("foo".parse::<Column>().is_err());
+/// ```
+impl FromStr for Column {
+    type Err = UnknownColumn;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let all = [
+            Column::Year,
+            Column::Tournament,
+            Column::BlackPlayer,
+            Column::WhitePlayer,
+            Column::BlackScore,
+            Column::TheoreticalScore,
+            Column::Moves,
+            Column::FinalPosition,
+            Column::Opening,
+        ];
+
+        all.iter()
+            .find(|c| c.name() == s.trim())
+            .copied()
+            .ok_or_else(|| UnknownColumn(s.to_string()))
+    }
+}
+
+/// Output format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Csv { delimiter: char, header: bool },
+    JsonLines,
+}
+
+// a column value is either a number, a string or missing
+enum Value {
+    Number(i32),
+    Text(String),
+    Missing,
+}
+
+/// Write game records, one per line
+pub struct Exporter {
+    pub columns: Vec<Column>,
+    pub format: Format,
+}
+
+impl Exporter {
+    pub fn new(columns: Vec<Column>, format: Format) -> Self {
+        Exporter { columns, format }
+    }
+
+    // compute the value of a column for a game
+    fn value(column: Column, game: &GameRecord) -> Value {
+        match column {
+            Column::Year => {
+                let year: String = game
+                    .date
+                    .as_deref()
+                    .unwrap_or("")
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect();
+                year.parse().map_or(Value::Missing, Value::Number)
+            }
+            Column::Tournament => game.tournament.clone().map_or(Value::Missing, Value::Text),
+            Column::BlackPlayer => Value::Text(game.black_player.clone()),
+            Column::WhitePlayer => Value::Text(game.white_player.clone()),
+            Column::BlackScore => game.result.map_or(Value::Missing, |r| {
+                Value::Number((r.round() as i32 + 64) / 2)
+            }),
+            Column::TheoreticalScore => game
+                .theoretical_score
+                .map_or(Value::Missing, |s| Value::Number(i32::from(s))),
+            Column::Moves => {
+                let moves: Vec<_> = game
+                    .moves
+                    .iter()
+                    .map(|rec| rec.mv)
+                    .filter(|mv| *mv != Move::Pass)
+                    .collect();
+                Value::Text(notation::moves_to_string(&moves, "-", Case::Upper))
+            }
+            Column::FinalPosition => Value::Text(game.final_position().to_squares('X', 'O', '-')),
+            Column::Opening => {
+                let moves: Vec<_> = game.moves.iter().map(|rec| rec.mv).collect();
+                opening::name(&moves).map_or(Value::Missing, |name| Value::Text(name.to_string()))
+            }
+        }
+    }
+
+    /// Write the CSV header if any
+    pub fn write_header<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        if let Format::Csv {
+            delimiter,
+            header: true,
+        } = self.format
+        {
+            let names: Vec<_> = self
+                .columns
+                .iter()
+                .map(|c| csv_quote(c.name(), delimiter))
+                .collect();
+            writeln!(w, "{}", names.join(&delimiter.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Write a single game
+    pub fn write_game<W: Write>(&self, w: &mut W, game: &GameRecord) -> io::Result<()> {
+        writeln!(w, "{}", self.format_game(game))
+    }
+
+    /// Format a single game as a line, without the line terminator
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::export::{Column, Exporter, Format};
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let moves: Vec<_> = ["F5", "D6", "C3", "D3", "C4"].iter().map(|m| m.parse().unwrap()).collect();
+    /// let mut game = GameRecord::from_moves(&moves).unwrap();
+    /// game.black_player = "Doe; John".to_string();
+    /// game.date = Some("2016".to_string());
+    ///
+    /// let columns = vec![Column::Year, Column::BlackPlayer, Column::BlackScore, Column::Opening];
+    ///
+    /// let csv = Exporter::new(columns.clone(), Format::Csv { delimiter: ';', header: true });
+    /// assert_eq!(csv.format_game(&game), r#"2016;"Doe; John";;Tiger"#);
+    ///
+    /// let json = Exporter::new(columns, Format::JsonLines);
+    /// assert_eq!(
+    ///     json.format_game(&game),
+    ///     r#"{"year":2016,"black":"Doe; John","black_score":null,"opening":"Tiger"}"#
+    /// );
+    /// ```
+    pub fn format_game(&self, game: &GameRecord) -> String {
+        let values = self.columns.iter().map(|c| (c, Exporter::value(*c, game)));
+
+        match self.format {
+            Format::Csv { delimiter, .. } => {
+                let fields: Vec<_> = values
+                    .map(|(_, v)| match v {
+                        Value::Number(n) => n.to_string(),
+                        Value::Text(s) => csv_quote(&s, delimiter),
+                        Value::Missing => String::new(),
+                    })
+                    .collect();
+                fields.join(&delimiter.to_string())
+            }
+            Format::JsonLines => {
+                let fields: Vec<_> = values
+                    .map(|(c, v)| {
+                        let value = match v {
+                            Value::Number(n) => n.to_string(),
+                            Value::Text(s) => json_quote(&s),
+                            Value::Missing => "null".to_string(),
+                        };
+                        format!("{}:{}", json_quote(c.name()), value)
+                    })
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+/// Quote a CSV field if it contains the delimiter, a double quote or a line break
+///
+/// # Examples
+/// ```
+/// use othlib::game::export::csv_quote;
+///
+/// assert_eq!(csv_quote("Hobo Roel", ';'), "Hobo Roel");
+/// assert_eq!(csv_quote("Open; 2016", ';'), "\"Open; 2016\"");
+/// assert_eq!(csv_quote("a \"nick\"", ','), "\"a \"\"nick\"\"\"");
+/// ```
+pub fn csv_quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Quote and escape a JSON string
+///
+/// # Examples
+/// ```
+/// use othlib::game::export::json_quote;
+///
+/// assert_eq!(json_quote("a \"b\"\\"), r#""a \"b\"\\""#);
+/// assert_eq!(json_quote("\u{1}"), r#""\u0001""#);
+/// ```
+pub fn json_quote(s: &str) -> String {
+    let mut quoted = String::with_capacity(s.len() + 2);
+
+    quoted.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
@@ -0,0 +1,307 @@
+// GGF (Generic Game Format) is the format used by the GGS server and most Othello tools.
+// A game looks like:
+//
+// (;GM[Othello]PC[GGS/os]DT[2003.09.29]PB[black]PW[white]RB[2155.21]RW[1946.88]TI[05:00//02:00]
+//   TY[8]RE[+14.000]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]
+//   B[f5//0.01]W[d6/-1.23/2.5]...;)
+//
+// Tags not used by GameRecord are silently ignored.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::color::Color;
+use crate::board::position::{Move, Position};
+use crate::game::record::{GameRecord, RecordError, RecordedMove};
+
+/// Errors found when reading a GGF game
+#[derive(Debug, Clone, PartialEq)]
+pub enum GgfError {
+    /// the text is not made of `(; TAG[value] ... ;)`
+    Syntax(String),
+    /// the BO tag can't be understood or is not an 8x8 board
+    InvalidBoard(String),
+    /// a B or W tag can't be understood
+    InvalidMove(String),
+    /// moves are not legal
+    Record(RecordError),
+}
+
+impl fmt::Display for GgfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GgfError::Syntax(msg) => write!(f, "GGF syntax error: {}", msg),
+            GgfError::InvalidBoard(board) => write!(f, "invalid GGF board '{}'", board),
+            GgfError::InvalidMove(mv) => write!(f, "invalid GGF move '{}'", mv),
+            GgfError::Record(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for GgfError {}
+
+impl From<RecordError> for GgfError {
+    fn from(e: RecordError) -> Self {
+        GgfError::Record(e)
+    }
+}
+
+// split the game content into (tag, value) pairs
+fn tags(content: &str) -> Result<Vec<(&str, &str)>, GgfError> {
+    let mut tags = Vec::new();
+    let mut rest = content.trim_start();
+
+    while !rest.is_empty() {
+        let open = rest
+            .find('[')
+            .ok_or_else(|| GgfError::Syntax(format!("missing '[' in '{}'", rest)))?;
+        let close = rest[open..]
+            .find(']')
+            .ok_or_else(|| GgfError::Syntax(format!("missing ']' in '{}'", rest)))?
+            + open;
+
+        let tag = rest[..open].trim();
+        if tag.is_empty() || !tag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(GgfError::Syntax(format!("invalid tag '{}'", tag)));
+        }
+
+        tags.push((tag, &rest[open + 1..close]));
+        rest = rest[close + 1..].trim_start();
+    }
+
+    Ok(tags)
+}
+
+// GGF board is the size, 64 squares with optional spaces, and the color to move
+fn parse_board(board: &str) -> Result<Position, GgfError> {
+    let invalid = || GgfError::InvalidBoard(board.to_string());
+
+    let board = board.trim();
+    let (size, rest) = board.split_at(board.find(char::is_whitespace).ok_or_else(invalid)?);
+    if size != "8" {
+        return Err(invalid());
+    }
+
+    let rest = rest.trim();
+    let side = match rest.chars().last() {
+        Some('*') => Color::Black,
+        Some('O') => Color::White,
+        _ => return Err(invalid()),
+    };
+
+    Position::from_squares(&rest[..rest.len() - 1], side).ok_or_else(invalid)
+}
+
+// times are either seconds or [[hh:]mm:]ss
+fn parse_time(time: &str) -> Option<f32> {
+    time.split(':').try_fold(0f32, |acc, part| {
+        part.parse::<f32>().ok().map(|v| acc * 60.0 + v)
+    })
+}
+
+fn parse_move(value: &str) -> Result<RecordedMove, GgfError> {
+    let invalid = || GgfError::InvalidMove(value.to_string());
+    let mut fields = value.split('/');
+
+    let mv = fields
+        .next()
+        .unwrap_or("")
+        .parse::<Move>()
+        .map_err(|_| invalid())?;
+
+    let eval = match fields.next().map(str::trim) {
+        None | Some("") => None,
+        Some(eval) => Some(eval.parse::<f32>().map_err(|_| invalid())?),
+    };
+
+    let time = match fields.next().map(str::trim) {
+        None | Some("") => None,
+        Some(time) => Some(parse_time(time).ok_or_else(invalid)?),
+    };
+
+    Ok(RecordedMove { mv, eval, time })
+}
+
+// result is a black disc differential, optionally followed by :r (resign), :t (timeout), ...
+fn parse_result(result: &str) -> Option<f32> {
+    result
+        .split(':')
+        .next()
+        .and_then(|r| r.trim().parse::<f32>().ok())
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+impl GameRecord {
+    /// Read a single GGF game
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let ggf = "(;GM[Othello]PC[GGS/os]PB[alice]PW[bob]RB[1850.2]RW[1790]TI[05:00//02:00]TY[8]RE[+4.000]\
+    ///     BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]\
+    ///     B[f5//1:02]W[d6/-1.50/3.25]B[c3];)";
+    ///
+    /// let game = GameRecord::from_ggf(ggf).unwrap();
+    /// assert_eq!(game.black_player, "alice");
+    /// assert_eq!(game.white_rating, Some(1790.0));
+    /// assert_eq!(game.result, Some(4.0));
+    /// assert_eq!(game.moves.len(), 3);
+    /// assert_eq!(game.moves[0].time, Some(62.0));
+    /// assert_eq!(game.moves[1].eval, Some(-1.5));
+    ///
+    /// // round trip
+    /// assert_eq!(GameRecord::from_ggf(&game.to_ggf()).unwrap(), game);
+    ///
+    /// // white can't play first
+    /// assert!(GameRecord::from_ggf("(;GM[Othello]W[d3];)").is_err());
+    /// ```
+    pub fn from_ggf(ggf: &str) -> Result<GameRecord, GgfError> {
+        let ggf = ggf.trim();
+        if !ggf.starts_with("(;") || !ggf.ends_with(";)") || ggf.len() < 4 {
+            return Err(GgfError::Syntax(
+                "game must be enclosed in '(;' and ';)'".to_string(),
+            ));
+        }
+
+        let mut game = GameRecord::default();
+        let mut moves = Vec::new();
+
+        for (tag, value) in tags(&ggf[2..ggf.len() - 2])? {
+            match tag {
+                "GM" if !value.eq_ignore_ascii_case("othello") => {
+                    return Err(GgfError::Syntax(format!(
+                        "not an Othello game: '{}'",
+                        value
+                    )))
+                }
+                "PC" => game.tournament = non_empty(value),
+                "DT" => game.date = non_empty(value),
+                "PB" => game.black_player = value.to_string(),
+                "PW" => game.white_player = value.to_string(),
+                "RB" => game.black_rating = value.trim().parse().ok(),
+                "RW" => game.white_rating = value.trim().parse().ok(),
+                "TI" => game.time_control = non_empty(value),
+                "RE" => game.result = parse_result(value),
+                "BO" => game.start = parse_board(value)?,
+                "B" => moves.push((Color::Black, parse_move(value)?)),
+                "W" => moves.push((Color::White, parse_move(value)?)),
+                _ => (),
+            }
+        }
+
+        // GGF moves are explicitly colored, so passes must be in the file
+        let mut position = game.start;
+        for (ply, (color, rec)) in moves.into_iter().enumerate() {
+            if color != position.side || position.play(rec.mv).is_err() {
+                return Err(RecordError::IllegalMove { ply, mv: rec.mv }.into());
+            }
+            game.moves.push(rec);
+        }
+
+        Ok(game)
+    }
+
+    /// Read all GGF games found in a text, e.g. a GGS archive with one game per line
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let text = "(;GM[Othello]B[f5];)\n(;GM[Othello]B[d3]W[c3];)";
+    /// let games = GameRecord::all_from_ggf(text).unwrap();
+    /// assert_eq!(games.len(), 2);
+    /// assert_eq!(games[1].moves.len(), 2);
+    /// ```
+    pub fn all_from_ggf(text: &str) -> Result<Vec<GameRecord>, GgfError> {
+        let mut games = Vec::new();
+        let mut rest = text;
+
+        while let Some(start) = rest.find("(;") {
+            let end = rest[start..]
+                .find(";)")
+                .ok_or_else(|| GgfError::Syntax("missing ';)' at end of game".to_string()))?
+                + start
+                + 2;
+
+            games.push(GameRecord::from_ggf(&rest[start..end])?);
+            rest = &rest[end..];
+        }
+
+        Ok(games)
+    }
+
+    /// Write the game as GGF
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let moves: Vec<_> = ["F5", "D6"].iter().map(|m| m.parse().unwrap()).collect();
+    /// let game = GameRecord::from_moves(&moves).unwrap();
+    ///
+    /// assert!(game.to_ggf().starts_with("(;GM[Othello]"));
+    /// assert!(game.to_ggf().ends_with("B[F5]W[D6];)"));
+    /// ```
+    pub fn to_ggf(&self) -> String {
+        let mut ggf = String::from("(;GM[Othello]");
+
+        if let Some(tournament) = &self.tournament {
+            ggf += &format!("PC[{}]", tournament);
+        }
+        if let Some(date) = &self.date {
+            ggf += &format!("DT[{}]", date);
+        }
+        ggf += &format!("PB[{}]PW[{}]", self.black_player, self.white_player);
+        if let Some(rating) = self.black_rating {
+            ggf += &format!("RB[{}]", rating);
+        }
+        if let Some(rating) = self.white_rating {
+            ggf += &format!("RW[{}]", rating);
+        }
+        if let Some(time_control) = &self.time_control {
+            ggf += &format!("TI[{}]", time_control);
+        }
+        ggf += "TY[8]";
+        if let Some(result) = self.result {
+            ggf += &format!("RE[{:+.3}]", result);
+        }
+
+        // starting position: rows are separated by a space
+        let squares = self.start.to_squares('*', 'O', '-');
+        let rows: Vec<_> = (0..8).map(|row| &squares[8 * row..8 * (row + 1)]).collect();
+        let side = match self.start.side {
+            Color::Black => '*',
+            Color::White => 'O',
+        };
+        ggf += &format!("BO[8 {} {}]", rows.join(" "), side);
+
+        // moves with optional evaluation and time
+        let mut color = self.start.side;
+        for rec in &self.moves {
+            let tag = match color {
+                Color::Black => 'B',
+                Color::White => 'W',
+            };
+
+            let eval = rec.eval.map_or(String::new(), |e| format!("{:.2}", e));
+            let time = rec.time.map_or(String::new(), |t| format!("{:.2}", t));
+            if eval.is_empty() && time.is_empty() {
+                ggf += &format!("{}[{}]", tag, rec.mv);
+            } else {
+                ggf += &format!("{}[{}/{}/{}]", tag, rec.mv, eval, time);
+            }
+
+            color = color.flip();
+        }
+
+        ggf + ";)"
+    }
+}
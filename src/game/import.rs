@@ -0,0 +1,334 @@
+// Import game records from move strings, from CSV files written by wthor2csv and from WTHOR game
+// files
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use crate::board::notation::{self, NotationError};
+use crate::board::position::Move;
+use crate::game::export::{Column, DEFAULT_COLUMNS};
+use crate::game::record::{GameRecord, RecordError};
+use crate::game::wthor::{WThorFile, WThorGame};
+
+/// What went wrong when importing a game
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportErrorKind {
+    /// a move can't be read
+    Notation(NotationError),
+    /// moves are not legal
+    Record(RecordError),
+    /// not enough fields on the line
+    MissingField(Column),
+    /// a numeric field can't be read
+    InvalidNumber(Column, String),
+    /// the file can't be read
+    Io(String),
+    /// a game of a WTHOR file (1-based) can't be replayed
+    WThorGame(usize, RecordError),
+}
+
+/// Error found when importing a game, with the line number (1-based) in the file if any
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    pub line: Option<usize>,
+    pub kind: ImportErrorKind,
+}
+
+impl ImportError {
+    fn at_line(self, line: usize) -> Self {
+        ImportError {
+            line: Some(line),
+            kind: self.kind,
+        }
+    }
+}
+
+impl From<ImportErrorKind> for ImportError {
+    fn from(kind: ImportErrorKind) -> Self {
+        ImportError { line: None, kind }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(line) = self.line {
+            write!(f, "line {}: ", line)?;
+        }
+        match &self.kind {
+            ImportErrorKind::Notation(e) => write!(f, "{}", e),
+            ImportErrorKind::Record(e) => write!(f, "{}", e),
+            ImportErrorKind::MissingField(column) => write!(f, "missing field '{}'", column.name()),
+            ImportErrorKind::InvalidNumber(column, value) => {
+                write!(
+                    f,
+                    "invalid number '{}' for field '{}'",
+                    value,
+                    column.name()
+                )
+            }
+            ImportErrorKind::Io(msg) => write!(f, "{}", msg),
+            ImportErrorKind::WThorGame(game, e) => write!(f, "game #{}: {}", game, e),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+/// Split a move string into moves. Moves are either separated by `-`, `,` or spaces
+/// (e.g. `F5-D6-C3`) or concatenated (e.g. `f5d6c3`), whatever the case. See
+/// `notation::parse_moves`.
+///
+/// # Examples
+/// ```
+/// use othlib::game::import::parse_moves;
+///
+/// let moves = parse_moves("F5-D6-C3").unwrap();
+/// assert_eq!(moves, parse_moves("f5d6c3").unwrap());
+/// assert_eq!(moves, parse_moves("f5 D6, c3").unwrap());
+/// assert_eq!(moves[0].to_string(), "F5");
+///
+/// let err = parse_moves("f5d6z9").unwrap_err();
+/// assert_eq!(err.to_string(), "invalid move 'z9' at ply 3");
+/// ```
+pub fn parse_moves(moves: &str) -> Result<Vec<Move>, ImportError> {
+    notation::parse_moves(moves).map_err(|e| ImportErrorKind::Notation(e).into())
+}
+
+/// Read a CSV delimiter given on the command line: a single character, or `tab`
+///
+/// # Examples
+/// ```
+/// use othlib::game::import::parse_delimiter;
+///
+/// assert_eq!(parse_delimiter(","), Some(','));
+/// assert_eq!(parse_delimiter("tab"), Some('\t'));
+/// assert_eq!(parse_delimiter("\\t"), Some('\t'));
+/// assert_eq!(parse_delimiter(";;"), None);
+/// ```
+pub fn parse_delimiter(delimiter: &str) -> Option<char> {
+    match delimiter {
+        "tab" | "\\t" => Some('\t'),
+        d if d.chars().count() == 1 => d.chars().next(),
+        _ => None,
+    }
+}
+
+impl GameRecord {
+    /// Build a validated game from a move string played from the standard position
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let game = GameRecord::from_move_string("f5d6c3d3c4").unwrap();
+    /// assert_eq!(game.moves.len(), 5);
+    ///
+    /// let err = GameRecord::from_move_string("F5-D6-A1").unwrap_err();
+    /// assert_eq!(err.to_string(), "illegal move A1 at ply 3");
+    /// ```
+    pub fn from_move_string(moves: &str) -> Result<GameRecord, ImportError> {
+        let moves = parse_moves(moves)?;
+
+        GameRecord::from_moves(&moves).map_err(|e| ImportErrorKind::Record(e).into())
+    }
+}
+
+// split a CSV line into fields, managing double quotes
+fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.is_empty() => quoted = true,
+            c if c == delimiter && !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    fields
+}
+
+/// Read CSV files written by wthor2csv. If the first line is a header, it gives the columns,
+/// otherwise the default wthor2csv columns are expected. Quoted fields can't span several lines.
+pub struct Importer {
+    pub delimiter: char,
+}
+
+impl Default for Importer {
+    fn default() -> Self {
+        Importer::new(';')
+    }
+}
+
+impl Importer {
+    pub fn new(delimiter: char) -> Self {
+        Importer { delimiter }
+    }
+
+    // build a game from the CSV fields
+    fn read_game(&self, columns: &[Column], fields: &[String]) -> Result<GameRecord, ImportError> {
+        let field = |column: Column| -> Result<&str, ImportError> {
+            columns
+                .iter()
+                .position(|c| *c == column)
+                .map(|i| fields.get(i).map(|f| f.as_str()))
+                .unwrap_or(Some(""))
+                .ok_or_else(|| ImportErrorKind::MissingField(column).into())
+        };
+        let number = |column: Column| -> Result<Option<u8>, ImportError> {
+            match field(column)?.trim() {
+                "" => Ok(None),
+                value => value
+                    .parse::<u8>()
+                    .map(Some)
+                    .map_err(|_| ImportErrorKind::InvalidNumber(column, value.to_string()).into()),
+            }
+        };
+
+        let mut game = GameRecord::from_move_string(field(Column::Moves)?)?;
+
+        let optional = |value: &str| match value {
+            "" => None,
+            _ => Some(value.to_string()),
+        };
+        game.tournament = optional(field(Column::Tournament)?);
+        game.date = optional(field(Column::Year)?);
+        game.black_player = field(Column::BlackPlayer)?.to_string();
+        game.white_player = field(Column::WhitePlayer)?.to_string();
+        game.result = number(Column::BlackScore)?.map(|score| 2.0 * f32::from(score) - 64.0);
+        game.theoretical_score = number(Column::TheoreticalScore)?;
+
+        Ok(game)
+    }
+
+    /// Read all games of a CSV text, one result per game line. Empty lines are skipped.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::import::Importer;
+    ///
+    /// let text = "black;white;moves\n\"Doe; John\";Smith;F5-D6-C3\nA;B;F5-F5\n";
+    /// let games = Importer::default().read_str(text);
+    ///
+    /// assert_eq!(games.len(), 2);
+    /// assert_eq!(games[0].as_ref().unwrap().black_player, "Doe; John");
+    /// assert_eq!(games[1].as_ref().unwrap_err().to_string(), "line 3: illegal move F5 at ply 2");
+    /// ```
+    pub fn read_str(&self, text: &str) -> Vec<Result<GameRecord, ImportError>> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .peekable();
+
+        // use header if any
+        let mut columns = DEFAULT_COLUMNS.to_vec();
+        if let Some((_, first)) = lines.peek() {
+            let header: Result<Vec<Column>, _> = split_csv_line(first, self.delimiter)
+                .iter()
+                .map(|name| name.parse::<Column>())
+                .collect();
+            if let Ok(header) = header {
+                columns = header;
+                lines.next();
+            }
+        }
+
+        lines
+            .map(|(i, line)| {
+                let fields = split_csv_line(line, self.delimiter);
+                self.read_game(&columns, &fields)
+                    .map_err(|e| e.at_line(i + 1))
+            })
+            .collect()
+    }
+
+    /// Read all games of a CSV file, stopping at the first error
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::import::Importer;
+    ///
+    /// let games = Importer::default().read_file("wthor/wthor_2016.csv").unwrap();
+    /// assert_eq!(games.len(), 2013);
+    /// assert_eq!(games[0].white_player, "Hobo Roel");
+    /// assert_eq!(games[0].result, Some(-4.0));
+    /// ```
+    pub fn read_file(&self, file_name: &str) -> Result<Vec<GameRecord>, ImportError> {
+        let text = fs::read_to_string(file_name)
+            .map_err(|e| ImportError::from(ImportErrorKind::Io(format!("{}: {}", file_name, e))))?;
+
+        self.read_str(&text).into_iter().collect()
+    }
+
+    /// Read all games of a WTHOR game file (`.wtb`) or of a CSV file, one result per game, so
+    /// that wrong games can be skipped. Fails if the file can't be read, or is a truncated
+    /// WTHOR file.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::import::Importer;
+    /// use othlib::game::record::GameRecord;
+    /// use othlib::game::wthor::{write_games, WThorGame};
+    ///
+    /// let importer = Importer::default();
+    /// let games = importer.read_games("wthor/wthor_2016.csv").unwrap();
+    /// assert_eq!(games.len(), 2013);
+    ///
+    /// // a WTHOR file whose second game starts in a corner
+    /// let game = GameRecord::from_move_string("f5d6c3").unwrap().to_wthor().unwrap();
+    /// let wrong = WThorGame { moves: vec![11], ..game.clone() };
+    ///
+    /// let path = std::env::temp_dir().join("othlib_read_games.wtb");
+    /// let mut file = std::fs::File::create(&path).unwrap();
+    /// write_games(&mut file, &[game, wrong], 2024).unwrap();
+    /// drop(file);
+    ///
+    /// let games = importer.read_games(path.to_str().unwrap()).unwrap();
+    /// assert_eq!(games[0].as_ref().unwrap().moves.len(), 3);
+    /// assert_eq!(games[1].as_ref().unwrap_err().to_string(), "game #2: illegal move A1 at ply 1");
+    ///
+    /// // the same file without its last byte
+    /// let bytes = std::fs::read(&path).unwrap();
+    /// std::fs::write(&path, &bytes[..bytes.len() - 1]).unwrap();
+    /// let err = importer.read_games(path.to_str().unwrap()).unwrap_err();
+    /// assert!(err.to_string().ends_with("truncated WTHOR file: record #2 of 2"));
+    /// # std::fs::remove_file(path).unwrap();
+    ///
+    /// assert!(importer.read_games("missing.wtb").is_err());
+    /// ```
+    pub fn read_games(
+        &self,
+        file_name: &str,
+    ) -> Result<Vec<Result<GameRecord, ImportError>>, ImportError> {
+        let io_error = |e: std::io::Error| {
+            ImportError::from(ImportErrorKind::Io(format!("{}: {}", file_name, e)))
+        };
+
+        if !file_name.to_lowercase().ends_with(".wtb") {
+            let text = fs::read_to_string(file_name).map_err(io_error)?;
+            return Ok(self.read_str(&text));
+        }
+
+        Ok(WThorFile::<WThorGame>::new(file_name)
+            .map_err(io_error)?
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, game)| {
+                GameRecord::from_wthor(game)
+                    .map_err(|e| ImportErrorKind::WThorGame(i + 1, e).into())
+            })
+            .collect())
+    }
+}
@@ -1 +1,7 @@
+pub mod export;
+pub mod ggf;
+pub mod import;
+pub mod obf;
+pub mod opening;
+pub mod record;
 pub mod wthor;
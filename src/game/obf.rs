@@ -0,0 +1,181 @@
+// OBF (Othello Board Format) is the text format of test positions like the FFO endgame suite.
+// A line looks like:
+//
+// O--OOOOX-OOOOOOXOOXXOOOXOOXOOOXXOOOOOOXX---OOOOX----O--X-------- X; A2:+38; B1:+36;
+//
+// i.e. 64 squares from A1 to H8 (X black, O white, - empty), the color to move, then optionally
+// some moves with their exact score, as a disc differential for the color to move. Empty lines
+// and lines starting with `%` or `#` are comments.
+
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::board::color::Color;
+use crate::board::notation;
+use crate::board::position::{Move, Position};
+
+/// FFO endgame test positions shipped with the crate in `data/ffo.obf`, numbered from
+/// `FFO_FIRST`
+///
+/// # Examples
+/// ```
+/// use othlib::game::obf::{ObfPosition, FFO_FIRST, FFO_OBF};
+///
+/// let positions = ObfPosition::all_from_obf(FFO_OBF).unwrap();
+/// assert_eq!(FFO_FIRST, 40);
+/// assert_eq!(positions[0].best_moves()[0].to_string(), "A2");
+/// assert_eq!(positions[0].best_score(), Some(38));
+/// ```
+pub const FFO_OBF: &str = include_str!("../../data/ffo.obf");
+
+/// Number of the first position of `FFO_OBF`
+pub const FFO_FIRST: usize = 40;
+
+/// Errors found when reading an OBF position
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObfError {
+    /// squares or color to move can't be understood
+    InvalidBoard(String),
+    /// a scored move is not `move:score`, or the move is not legal
+    InvalidMove(String),
+}
+
+impl fmt::Display for ObfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ObfError::InvalidBoard(board) => write!(f, "invalid OBF board '{}'", board),
+            ObfError::InvalidMove(mv) => write!(f, "invalid OBF move '{}'", mv),
+        }
+    }
+}
+
+impl Error for ObfError {}
+
+/// A test position with the known scores of some of its moves
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObfPosition {
+    pub position: Position,
+    pub scores: Vec<(Move, i32)>,
+}
+
+impl ObfPosition {
+    /// The best known score, if any
+    pub fn best_score(&self) -> Option<i32> {
+        self.scores.iter().map(|(_, score)| *score).max()
+    }
+
+    /// All moves reaching the best known score
+    pub fn best_moves(&self) -> Vec<Move> {
+        match self.best_score() {
+            Some(best) => self
+                .scores
+                .iter()
+                .filter(|(_, score)| *score == best)
+                .map(|(mv, _)| *mv)
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Read all positions of an OBF text, skipping comments and empty lines
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::obf::ObfPosition;
+    ///
+    /// let text = "% FFO #40\n\
+    ///     O--OOOOX-OOOOOOXOOXXOOOXOOXOOOXXOOOOOOXX---OOOOX----O--X-------- X; A2:+38;\n\
+    ///     \n\
+    ///     ---------------------------OX------XO--------------------------- X";
+    ///
+    /// let positions = ObfPosition::all_from_obf(text).unwrap();
+    /// assert_eq!(positions.len(), 2);
+    /// assert_eq!(positions[0].position.nb_empty(), 20);
+    /// assert_eq!(positions[1].best_score(), None);
+    /// ```
+    pub fn all_from_obf(text: &str) -> Result<Vec<ObfPosition>, ObfError> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('%') && !line.starts_with('#'))
+            .map(str::parse)
+            .collect()
+    }
+}
+
+/// Read a single OBF line
+///
+/// # Examples
+/// ```
+/// use othlib::board::color::Color;
+/// use othlib::game::obf::ObfPosition;
+///
+/// let line = "O--OOOOX-OOOOOOXOOXXOOOXOOXOOOXXOOOOOOXX---OOOOX----O--X-------- X; A2:+38; B1:+36;";
+/// let obf: ObfPosition = line.parse().unwrap();
+///
+/// assert_eq!(obf.position.side, Color::Black);
+/// assert_eq!(obf.best_score(), Some(38));
+/// assert_eq!(obf.best_moves()[0].to_string(), "A2");
+///
+/// // round trip
+/// assert_eq!(obf.to_string(), line);
+///
+/// // H8 is not empty
+/// assert!(line.replace("A2", "H8").parse::<ObfPosition>().is_err());
+/// ```
+impl FromStr for ObfPosition {
+    type Err = ObfError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid_board = || ObfError::InvalidBoard(s.to_string());
+
+        let mut fields = s.split(';').map(str::trim);
+        let mut board = fields.next().unwrap_or("").split_whitespace();
+
+        let squares = board.next().ok_or_else(invalid_board)?;
+        let side = match board.next() {
+            Some("X") | Some("x") | Some("*") => Color::Black,
+            Some("O") | Some("o") => Color::White,
+            _ => return Err(invalid_board()),
+        };
+        if board.next().is_some() {
+            return Err(invalid_board());
+        }
+        let position = Position::from_squares(squares, side).ok_or_else(invalid_board)?;
+
+        let mut scores = Vec::new();
+        for field in fields.filter(|field| !field.is_empty()) {
+            let invalid_move = || ObfError::InvalidMove(field.to_string());
+
+            let mut parts = field.split(':');
+            let mv =
+                notation::parse_move(parts.next().unwrap_or("")).map_err(|_| invalid_move())?;
+            let score = parts
+                .next()
+                .and_then(|score| score.trim().parse::<i32>().ok())
+                .ok_or_else(invalid_move)?;
+            if parts.next().is_some() || !position.is_legal(mv) {
+                return Err(invalid_move());
+            }
+            scores.push((mv, score));
+        }
+
+        Ok(ObfPosition { position, scores })
+    }
+}
+
+impl fmt::Display for ObfPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let side = match self.position.side {
+            Color::Black => 'X',
+            Color::White => 'O',
+        };
+        write!(f, "{} {};", self.position.to_squares('X', 'O', '-'), side)?;
+
+        for (mv, score) in &self.scores {
+            write!(f, " {}:{:+};", mv, score)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+// Names of a few well-known openings. As the starting position has 4 symmetries keeping colors
+// unchanged, moves are first transformed so that the game starts with F5.
+
+use crate::board::coordinate::Coordinate;
+use crate::board::position::Move;
+
+// when several lines match, the longest one wins
+const OPENINGS: [(&str, &str); 8] = [
+    ("F5D6", "Perpendicular"),
+    ("F5F6", "Parallel"),
+    ("F5F4", "Diagonal"),
+    ("F5D6C3D3C4", "Tiger"),
+    ("F5D6C5F4E3C6", "Cow"),
+    ("F5D6C5F4E3C6D3F6E6D7", "Rose"),
+    ("F5F6E6F4C3", "Buffalo"),
+    ("F5F6E6F4G5", "Heath"),
+];
+
+// the 4 symmetries of the starting position, applied on (x,y) coordinates
+fn transform(xy: (usize, usize), symmetry: usize) -> (usize, usize) {
+    match symmetry {
+        0 => xy,
+        1 => (xy.1, xy.0),
+        2 => (7 - xy.1, 7 - xy.0),
+        _ => (7 - xy.0, 7 - xy.1),
+    }
+}
+
+/// Return the moves as a string of algebric coordinates, transformed so that the first move is F5
+///
+/// # Examples
+/// ```
+/// use othlib::game::opening::normalize;
+///
+/// let moves: Vec<_> = ["D3", "C3", "C4"].iter().map(|m| m.parse().unwrap()).collect();
+/// assert_eq!(normalize(&moves), "F5F6E6");
+/// ```
+pub fn normalize(moves: &[Move]) -> String {
+    let f5 = Coordinate::from_algebric("F5");
+
+    // find the symmetry mapping the first move to F5
+    let symmetry = match moves.first() {
+        Some(Move::Play(square)) => (0..4)
+            .find(|&s| transform(Coordinate::to_bitboard(*square), s) == f5)
+            .unwrap_or(0),
+        _ => 0,
+    };
+
+    moves
+        .iter()
+        .map(|mv| match mv {
+            Move::Play(square) => {
+                Coordinate::to_algebric(transform(Coordinate::to_bitboard(*square), symmetry))
+            }
+            Move::Pass => mv.to_string(),
+        })
+        .collect()
+}
+
+/// Return the name of the longest known opening matching the first moves of a game
+///
+/// # Examples
+/// ```
+/// use othlib::game::opening::name;
+///
+/// let moves: Vec<_> = ["F5", "D6", "C3", "D3", "C4", "F4"].iter().map(|m| m.parse().unwrap()).collect();
+/// assert_eq!(name(&moves), Some("Tiger"));
+///
+/// // same opening, with a symmetric first move
+/// let moves: Vec<_> = ["E6", "F4"].iter().map(|m| m.parse().unwrap()).collect();
+/// assert_eq!(name(&moves), Some("Perpendicular"));
+///
+/// assert_eq!(name(&[]), None);
+/// ```
+pub fn name(moves: &[Move]) -> Option<&'static str> {
+    let line = normalize(moves);
+
+    OPENINGS
+        .iter()
+        .filter(|(prefix, _)| line.starts_with(prefix))
+        .max_by_key(|(prefix, _)| prefix.len())
+        .map(|(_, name)| *name)
+}
@@ -0,0 +1,276 @@
+// A game record is the common representation of a game, whatever the file format it's coming from:
+// WTHOR, GGF, ...
+
+use std::error::Error;
+use std::fmt;
+
+use crate::board::notation;
+use crate::board::position::{IllegalMove, Move, Position};
+use crate::game::wthor::WThorGame;
+
+/// A move as found in a game record, with optional evaluation and elapsed time in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RecordedMove {
+    pub mv: Move,
+    pub eval: Option<f32>,
+    pub time: Option<f32>,
+}
+
+impl RecordedMove {
+    pub fn new(mv: Move) -> Self {
+        RecordedMove {
+            mv,
+            eval: None,
+            time: None,
+        }
+    }
+}
+
+/// Errors found when building or converting a game record
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// the move at this ply (0-based) is not legal
+    IllegalMove { ply: usize, mv: Move },
+    /// a WTHOR move byte which is not a square
+    InvalidWThorMove { ply: usize, byte: u8 },
+    /// WTHOR games always start from the standard position
+    NonStandardStart,
+}
+
+impl fmt::Display for RecordError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RecordError::IllegalMove { ply, mv } => {
+                write!(f, "illegal move {} at ply {}", mv, ply + 1)
+            }
+            RecordError::InvalidWThorMove { ply, byte } => {
+                write!(f, "invalid WTHOR move {} at ply {}", byte, ply + 1)
+            }
+            RecordError::NonStandardStart => {
+                write!(f, "game doesn't start from the standard position")
+            }
+        }
+    }
+}
+
+impl Error for RecordError {}
+
+/// A full game: players, result, starting position and moves. Deserialized games aren't checked,
+/// use `validate` before replaying them.
+///
+/// # Examples
+/// ```
+/// use othlib::game::record::GameRecord;
+///
+/// # #[cfg(feature = "serde")] {
+/// let mut game = GameRecord::from_move_string("f5d6").unwrap();
+/// game.black_player = String::from("Tastet");
+/// game.moves[1].eval = Some(-1.5);
+///
+/// let json = serde_json::to_string(&game).unwrap();
+/// assert!(json.contains(r#""black_player":"Tastet""#));
+/// assert!(json.contains(r#"{"mv":"D6","eval":-1.5,"time":null}"#));
+/// assert_eq!(serde_json::from_str::<GameRecord>(&json).unwrap(), game);
+///
+/// let bytes = bincode::serialize(&game).unwrap();
+/// assert_eq!(bincode::deserialize::<GameRecord>(&bytes).unwrap(), game);
+///
+/// // moves are only checked when validating
+/// let illegal: GameRecord = serde_json::from_str(&json.replace("D6", "A1")).unwrap();
+/// assert!(illegal.validate().is_err());
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameRecord {
+    pub tournament: Option<String>,
+    pub date: Option<String>,
+    pub black_player: String,
+    pub white_player: String,
+    pub black_rating: Option<f32>,
+    pub white_rating: Option<f32>,
+    pub time_control: Option<String>,
+    /// disc differential from black's point of view
+    pub result: Option<f32>,
+    /// WTHOR theoretical score (black discs with perfect play from some depth)
+    pub theoretical_score: Option<u8>,
+    pub start: Position,
+    pub moves: Vec<RecordedMove>,
+}
+
+impl Default for GameRecord {
+    fn default() -> Self {
+        GameRecord::new(Position::new())
+    }
+}
+
+impl GameRecord {
+    /// An empty game starting from `start`
+    pub fn new(start: Position) -> Self {
+        GameRecord {
+            tournament: None,
+            date: None,
+            black_player: String::new(),
+            white_player: String::new(),
+            black_rating: None,
+            white_rating: None,
+            time_control: None,
+            result: None,
+            theoretical_score: None,
+            start,
+            moves: Vec::new(),
+        }
+    }
+
+    /// Build a game from the standard position by playing a list of moves. Passes are
+    /// inserted when a color has no legal move, so they can be omitted.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    ///
+    /// let moves: Vec<_> = ["F5", "D6", "C3"].iter().map(|m| m.parse().unwrap()).collect();
+    /// let game = GameRecord::from_moves(&moves).unwrap();
+    /// assert_eq!(game.moves.len(), 3);
+    /// assert_eq!(game.final_position().nb_empty(), 57);
+    ///
+    /// let wrong: Vec<_> = ["F5", "F4", "A1"].iter().map(|m| m.parse().unwrap()).collect();
+    /// assert!(GameRecord::from_moves(&wrong).is_err());
+    /// ```
+    pub fn from_moves(moves: &[Move]) -> Result<Self, RecordError> {
+        let mut game = GameRecord::default();
+        let mut position = game.start;
+
+        for (ply, mv) in moves.iter().enumerate() {
+            game.play(&mut position, RecordedMove::new(*mv), ply)?;
+        }
+
+        Ok(game)
+    }
+
+    // play a move on `position`, inserting a pass first if needed
+    fn play(
+        &mut self,
+        position: &mut Position,
+        rec: RecordedMove,
+        ply: usize,
+    ) -> Result<(), RecordError> {
+        if rec.mv != Move::Pass && position.must_pass() {
+            position.play(Move::Pass).unwrap();
+            self.moves.push(RecordedMove::new(Move::Pass));
+        }
+
+        position
+            .play(rec.mv)
+            .map_err(|IllegalMove(mv)| RecordError::IllegalMove { ply, mv })?;
+        self.moves.push(rec);
+
+        Ok(())
+    }
+
+    /// Append a move after checking it's legal in the current final position
+    pub fn push_move(&mut self, rec: RecordedMove) -> Result<(), RecordError> {
+        let mut position = self.final_position();
+        let ply = self.moves.len();
+
+        self.play(&mut position, rec, ply)
+    }
+
+    /// Position after `ply` moves. Moves are supposed to be legal.
+    pub fn position_at(&self, ply: usize) -> Position {
+        let mut position = self.start;
+
+        for rec in self.moves.iter().take(ply) {
+            position
+                .play(rec.mv)
+                .expect("game record contains an illegal move");
+        }
+
+        position
+    }
+
+    /// Position once all moves are played
+    pub fn final_position(&self) -> Position {
+        self.position_at(self.moves.len())
+    }
+
+    /// Check all moves are legal from the starting position
+    pub fn validate(&self) -> Result<(), RecordError> {
+        let mut position = self.start;
+
+        for (ply, rec) in self.moves.iter().enumerate() {
+            position
+                .play(rec.mv)
+                .map_err(|IllegalMove(mv)| RecordError::IllegalMove { ply, mv })?;
+        }
+
+        Ok(())
+    }
+
+    /// Build a game record from a WTHOR game. Player and tournament names are not resolved here
+    /// because they are in separate files.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    /// use othlib::game::wthor::WThorGame;
+    ///
+    /// let wthor = WThorGame {
+    ///     tournament_title_id: 0,
+    ///     black_player_id: 0,
+    ///     white_player_id: 0,
+    ///     black_pieces_id: 32,
+    ///     theoretical_score: 32,
+    ///     moves: vec![56, 64, 33],
+    /// };
+    ///
+    /// let game = GameRecord::from_wthor(&wthor).unwrap();
+    /// assert_eq!(game.moves[0].mv.to_string(), "F5");
+    /// assert_eq!(game.result, Some(0.0));
+    /// assert_eq!(game.to_wthor().unwrap().moves, wthor.moves);
+    /// ```
+    pub fn from_wthor(wthor: &WThorGame) -> Result<Self, RecordError> {
+        let mut game = GameRecord::default();
+        let mut position = game.start;
+
+        for (ply, byte) in wthor.moves.iter().enumerate() {
+            let square = notation::wthor_to_square(*byte)
+                .map_err(|_| RecordError::InvalidWThorMove { ply, byte: *byte })?;
+            game.play(&mut position, RecordedMove::new(Move::Play(square)), ply)?;
+        }
+
+        game.result = Some(2.0 * f32::from(wthor.black_pieces_id) - 64.0);
+        game.theoretical_score = Some(wthor.theoretical_score);
+
+        Ok(game)
+    }
+
+    /// Convert to a WTHOR game. Passes are dropped and player/tournament ids are left to 0.
+    pub fn to_wthor(&self) -> Result<WThorGame, RecordError> {
+        if self.start != Position::new() {
+            return Err(RecordError::NonStandardStart);
+        }
+        self.validate()?;
+
+        // moves are legal so they are all valid squares
+        let moves: Vec<Move> = self.moves.iter().map(|rec| rec.mv).collect();
+        let moves = notation::moves_to_wthor(&moves).unwrap();
+
+        // black discs, empty squares going to the winner
+        let score = match self.result {
+            Some(result) => result.round() as i32,
+            None => self.final_position().final_score(),
+        };
+        let black_pieces = ((score + 64) / 2).clamp(0, 64) as u8;
+
+        Ok(WThorGame {
+            tournament_title_id: 0,
+            black_player_id: 0,
+            white_player_id: 0,
+            black_pieces_id: black_pieces,
+            theoretical_score: self.theoretical_score.unwrap_or(black_pieces),
+            moves,
+        })
+    }
+}
@@ -2,82 +2,110 @@
 // Data format is described here: http://www.ffothello.org/wthor/Format_WThor.pdf
 
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read, Write};
 use std::mem;
 
-use byteorder::{LittleEndian, ReadBytesExt};
+use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
 
 // trait to implement to read specific parts of WTHOR files: games, players, ...
 pub trait WThorable<T> {
-    fn read_specific_data(wthor_file: &mut File) -> T;
+    fn read_specific_data(wthor_file: &mut File) -> io::Result<T>;
     fn get_number_of_records(n1_or_n2: (u32, u16)) -> u32;
 }
 
 // This header is common to all WTHOR files
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WThorFileHeader {
-    file_date: u32, // 4 bytes for describing WTHOR file date
-    n1: u32,        // n1 is the number of games in a WTHOR game file
-    n2: u16, // n1 is either the number of players in a players file or the number of tournaments in a tournament file
-    game_year: u16,
-    p1: u8, // p1 gives the board size: 0 or 8 for 8x8, 10 for 10x10 boards
-    p2: u8, // N/A for game files
-    p3: u8, // not used yet
-    reserved: u8,
+    pub file_date: u32, // 4 bytes for describing WTHOR file date
+    pub n1: u32,        // n1 is the number of games in a WTHOR game file
+    pub n2: u16, // n1 is either the number of players in a players file or the number of tournaments in a tournament file
+    pub game_year: u16,
+    pub p1: u8, // p1 gives the board size: 0 or 8 for 8x8, 10 for 10x10 boards
+    pub p2: u8, // N/A for game files
+    pub p3: u8, // not used yet
+    pub reserved: u8,
 }
 
 impl WThorFileHeader {
-    pub fn new(wthor_file: &mut File) -> Self {
+    pub fn new(wthor_file: &mut File) -> io::Result<Self> {
+        Ok(WThorFileHeader {
+            file_date: wthor_file.read_u32::<LittleEndian>()?,
+            n1: wthor_file.read_u32::<LittleEndian>()?,
+            n2: wthor_file.read_u16::<LittleEndian>()?,
+            game_year: wthor_file.read_u16::<LittleEndian>()?,
+            p1: wthor_file.read_u8()?,
+            p2: wthor_file.read_u8()?,
+            p3: wthor_file.read_u8()?,
+            reserved: wthor_file.read_u8()?,
+        })
+    }
+
+    /// Header of a game file for 8x8 boards
+    pub fn for_games(n1: u32, game_year: u16) -> Self {
         WThorFileHeader {
-            file_date: wthor_file.read_u32::<LittleEndian>().unwrap(),
-            n1: wthor_file.read_u32::<LittleEndian>().unwrap(),
-            n2: wthor_file.read_u16::<LittleEndian>().unwrap(),
-            game_year: wthor_file.read_u16::<LittleEndian>().unwrap(),
-            p1: wthor_file.read_u8().unwrap(),
-            p2: wthor_file.read_u8().unwrap(),
-            p3: wthor_file.read_u8().unwrap(),
-            reserved: wthor_file.read_u8().unwrap(),
+            file_date: 0,
+            n1,
+            n2: 0,
+            game_year,
+            p1: 8,
+            p2: 0,
+            p3: 0,
+            reserved: 0,
         }
     }
+
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_u32::<LittleEndian>(self.file_date)?;
+        w.write_u32::<LittleEndian>(self.n1)?;
+        w.write_u16::<LittleEndian>(self.n2)?;
+        w.write_u16::<LittleEndian>(self.game_year)?;
+        w.write_u8(self.p1)?;
+        w.write_u8(self.p2)?;
+        w.write_u8(self.p3)?;
+        w.write_u8(self.reserved)
+    }
 }
 
 // For 8x8 or 10x10 games
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WThorGame {
     pub tournament_title_id: u16,
     pub black_player_id: u16,
     pub white_player_id: u16,
     pub black_pieces_id: u8,
     pub theoretical_score: u8,
+    #[cfg_attr(feature = "serde", serde(with = "algebric_moves"))]
     pub moves: Vec<u8>,
 }
 
 impl WThorable<WThorGame> for WThorGame {
-    fn read_specific_data(wthor_file: &mut File) -> Self {
-        WThorGame {
-            tournament_title_id: wthor_file.read_u16::<LittleEndian>().unwrap(),
-            black_player_id: wthor_file.read_u16::<LittleEndian>().unwrap(),
-            white_player_id: wthor_file.read_u16::<LittleEndian>().unwrap(),
-            black_pieces_id: wthor_file.read_u8().unwrap(),
-            theoretical_score: wthor_file.read_u8().unwrap(),
+    fn read_specific_data(wthor_file: &mut File) -> io::Result<Self> {
+        Ok(WThorGame {
+            tournament_title_id: wthor_file.read_u16::<LittleEndian>()?,
+            black_player_id: wthor_file.read_u16::<LittleEndian>()?,
+            white_player_id: wthor_file.read_u16::<LittleEndian>()?,
+            black_pieces_id: wthor_file.read_u8()?,
+            theoretical_score: wthor_file.read_u8()?,
             moves: {
                 // 60 is the maximum number of moves (64-4)
                 let mut buffer = [0; 60];
 
                 // read 60 bytes, convert to a vector and delete 0 because the game is over
-                wthor_file.read_exact(&mut buffer).unwrap();
+                wthor_file.read_exact(&mut buffer)?;
 
                 let mut moves = buffer.to_vec();
                 moves.retain(|&x| x != 0);
 
                 // as bytes represent row,col coordinates, the spread from 11 to 88. We can test this here
-                if !moves.iter().all(|&x| (x >= 11) & (x <= 88)) {
+                if !moves.iter().all(|x| (11..=88).contains(x)) {
                     eprintln!("{:?}", moves);
                 }
 
                 moves
             },
-        }
+        })
     }
 
     fn get_number_of_records(n1_or_n2: (u32, u16)) -> u32 {
@@ -85,8 +113,104 @@ impl WThorable<WThorGame> for WThorGame {
     }
 }
 
+impl WThorGame {
+    /// Write the game record, moves being padded with 0 up to 60 bytes
+    pub fn write<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        w.write_u16::<LittleEndian>(self.tournament_title_id)?;
+        w.write_u16::<LittleEndian>(self.black_player_id)?;
+        w.write_u16::<LittleEndian>(self.white_player_id)?;
+        w.write_u8(self.black_pieces_id)?;
+        w.write_u8(self.theoretical_score)?;
+
+        let mut buffer = [0; 60];
+        let n = self.moves.len().min(60);
+        buffer[..n].copy_from_slice(&self.moves[..n]);
+        w.write_all(&buffer)
+    }
+}
+
+/// Moves of a game as algebric squares in text formats like JSON, and as WTHOR bytes in binary
+/// formats like MessagePack or bincode
+///
+/// # Examples
+/// ```
+/// use othlib::game::record::GameRecord;
+/// use othlib::game::wthor::WThorGame;
+///
+/// let game = GameRecord::from_move_string("f5d6c3").unwrap().to_wthor().unwrap();
+///
+/// let json = serde_json::to_string(&game).unwrap();
+/// assert!(json.ends_with(r#""moves":["F5","D6","C3"]}"#));
+/// assert_eq!(serde_json::from_str::<WThorGame>(&json).unwrap(), game);
+/// assert!(serde_json::from_str::<WThorGame>(&json.replace("C3", "Z3")).is_err());
+///
+/// let msgpack = rmp_serde::to_vec(&game).unwrap();
+/// assert_eq!(rmp_serde::from_slice::<WThorGame>(&msgpack).unwrap(), game);
+///
+/// let bytes = bincode::serialize(&game).unwrap();
+/// assert_eq!(bincode::deserialize::<WThorGame>(&bytes).unwrap(), game);
+/// ```
+#[cfg(feature = "serde")]
+mod algebric_moves {
+    use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::board::notation::{algebric_to_wthor, wthor_to_algebric, Case};
+
+    pub fn serialize<S: Serializer>(moves: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            return moves.serialize(serializer);
+        }
+
+        moves
+            .iter()
+            .map(|&byte| wthor_to_algebric(byte, Case::Upper))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(ser::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        if !deserializer.is_human_readable() {
+            return Vec::<u8>::deserialize(deserializer);
+        }
+
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|square| algebric_to_wthor(square).map_err(de::Error::custom))
+            .collect()
+    }
+}
+
+/// Write a full WTHOR game file: header then games
+///
+/// # Examples
+/// ```
+/// use othlib::game::record::GameRecord;
+/// use othlib::game::wthor::{write_games, WThorFile, WThorGame};
+///
+/// let game = GameRecord::from_move_string("f5d6c3d3c4").unwrap().to_wthor().unwrap();
+///
+/// let path = std::env::temp_dir().join("othlib_write_games.wtb");
+/// let mut file = std::fs::File::create(&path).unwrap();
+/// write_games(&mut file, &[game.clone(), game.clone()], 2024).unwrap();
+/// drop(file);
+///
+/// let read = WThorFile::<WThorGame>::new(path.to_str().unwrap()).unwrap();
+/// assert_eq!(read.header.game_year, 2024);
+/// assert_eq!(read.data, vec![game.clone(), game]);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub fn write_games<W: Write>(w: &mut W, games: &[WThorGame], game_year: u16) -> io::Result<()> {
+    WThorFileHeader::for_games(games.len() as u32, game_year).write(w)?;
+    for game in games {
+        game.write(w)?;
+    }
+    Ok(())
+}
+
 // A WTHOR file for the players
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WThorPlayer {
     pub player: String,
 }
@@ -95,15 +219,15 @@ pub struct WThorPlayer {
 const PLAYER_LENGTH: usize = 20;
 
 impl WThorable<WThorPlayer> for WThorPlayer {
-    fn read_specific_data(wthor_file: &mut File) -> Self {
-        WThorPlayer {
+    fn read_specific_data(wthor_file: &mut File) -> io::Result<Self> {
+        Ok(WThorPlayer {
             player: {
                 // WTHOR spec states a player name is a least 19 chars + \0
                 let mut buffer = [0; PLAYER_LENGTH];
-                wthor_file.read_exact(&mut buffer).unwrap();
-                String::from_utf8_lossy(&buffer.to_vec()).replace("\0", "")
+                wthor_file.read_exact(&mut buffer)?;
+                String::from_utf8_lossy(&buffer).replace("\0", "")
             },
-        }
+        })
     }
 
     fn get_number_of_records(n1_or_n2: (u32, u16)) -> u32 {
@@ -115,21 +239,22 @@ impl WThorable<WThorPlayer> for WThorPlayer {
 const TOURNEMENT_LENGTH: usize = 26;
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct WThorTournament {
     pub tournament: String,
 }
 
 // remove NULL chars
 impl WThorable<WThorTournament> for WThorTournament {
-    fn read_specific_data(wthor_file: &mut File) -> Self {
-        WThorTournament {
+    fn read_specific_data(wthor_file: &mut File) -> io::Result<Self> {
+        Ok(WThorTournament {
             tournament: {
                 // WTHOR spec states a player name is a least 19 chars + \0
                 let mut buffer = [0; TOURNEMENT_LENGTH];
-                wthor_file.read_exact(&mut buffer).unwrap();
-                String::from_utf8_lossy(&buffer.to_vec()).replace("\0", "")
+                wthor_file.read_exact(&mut buffer)?;
+                String::from_utf8_lossy(&buffer).replace("\0", "")
             },
-        }
+        })
     }
 
     fn get_number_of_records(n1_or_n2: (u32, u16)) -> u32 {
@@ -148,12 +273,36 @@ impl<T> WThorFile<T>
 where
     T: WThorable<T>,
 {
-    pub fn new(wthor_file_name: &str) -> Self {
+    /// Read a WTHOR file. Fails if it can't be opened, or if it is truncated, i.e. shorter than
+    /// the number of records given by its header.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::io::ErrorKind;
+    ///
+    /// use othlib::game::wthor::{WThorFile, WThorGame};
+    ///
+    /// let missing = WThorFile::<WThorGame>::new("missing.wtb").unwrap_err();
+    /// assert_eq!(missing.kind(), ErrorKind::NotFound);
+    ///
+    /// // a header announcing 2 games, followed by 30 bytes
+    /// let mut bytes = vec![0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0xe8, 0x07, 8, 0, 0, 0];
+    /// bytes.extend_from_slice(&[0; 30]);
+    /// let path = std::env::temp_dir().join("othlib_truncated.wtb");
+    /// std::fs::write(&path, bytes).unwrap();
+    ///
+    /// let truncated = WThorFile::<WThorGame>::new(path.to_str().unwrap()).unwrap_err();
+    /// assert_eq!(truncated.kind(), ErrorKind::UnexpectedEof);
+    /// assert_eq!(truncated.to_string(), "truncated WTHOR file: record #1 of 2");
+    /// # std::fs::remove_file(path).unwrap();
+    /// ```
+    pub fn new(wthor_file_name: &str) -> io::Result<Self> {
         // open file
-        let mut wthor_file = File::open(wthor_file_name).expect("Unable to open WTHOR file");
+        let mut wthor_file = File::open(wthor_file_name)?;
 
         // get header to get the number of records
-        let header = WThorFileHeader::new(&mut wthor_file);
+        let header = WThorFileHeader::new(&mut wthor_file)
+            .map_err(|e| truncated(e, "header".to_string()))?;
 
         // read specific data and add them to the vector
         let mut buffer = Vec::<T>::with_capacity(mem::size_of::<T>());
@@ -162,16 +311,30 @@ where
         let n = T::get_number_of_records((header.n1, header.n2));
 
         // just add our specific data
-        for _i in 1..=n {
-            buffer.push(T::read_specific_data(&mut wthor_file));
+        for i in 1..=n {
+            let record = T::read_specific_data(&mut wthor_file)
+                .map_err(|e| truncated(e, format!("record #{} of {}", i, n)))?;
+            buffer.push(record);
         }
 
         // close file
         drop(wthor_file);
 
-        WThorFile {
-            header: header,
+        Ok(WThorFile {
+            header,
             data: buffer,
-        }
+        })
+    }
+}
+
+// an end of file met while reading `what` means the file is truncated
+fn truncated(e: io::Error, what: String) -> io::Error {
+    if e.kind() == io::ErrorKind::UnexpectedEof {
+        io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            format!("truncated WTHOR file: {}", what),
+        )
+    } else {
+        e
     }
 }
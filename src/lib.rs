@@ -2,6 +2,15 @@
 extern crate unic_char_range;
 
 pub mod board;
+pub mod engine;
 pub mod game;
+pub mod protocol;
 pub mod svg;
 pub mod util;
+
+#[cfg(feature = "capi")]
+pub mod capi;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "wasm")]
+pub mod wasm;
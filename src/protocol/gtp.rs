@@ -0,0 +1,320 @@
+// A line oriented text protocol in the spirit of GTP (the Go Text Protocol), for scripts and
+// match runners.
+//
+// Each command is a line, optionally starting with a numeric id. Successful commands are answered
+// by `=[id] result`, failed ones by `?[id] error`, and each answer ends with an empty line.
+// Commands are:
+//
+// * `protocol_version`, `name`, `version`, `known_command <name>`, `list_commands`, `quit`
+// * `boardsize <n>`: only 8 is accepted
+// * `clear_board`: back to the starting position
+// * `play <color> <move>`: play a move, e.g. `play black f5`. A pass is `pass`.
+// * `genmove <color>`: the engine plays for a color, and answers its move. Fails with
+//   `game over` once the game is finished.
+// * `undo`: take back the last move
+// * `list_legal [<color>]`: legal moves, separated by spaces
+// * `showboard`: the board as text
+// * `final_score`: e.g. `B+12`, `W+4` or `0`
+//
+// The protocol only deals with lines of text, so it can run over stdin and stdout or a socket.
+
+use std::io::{self, BufRead, Write};
+
+use crate::board::color::Color;
+use crate::board::position::{Move, Position};
+use crate::engine::limits::SearchLimits;
+use crate::engine::search::Engine;
+use crate::game::record::{GameRecord, RecordedMove};
+use crate::protocol::ENGINE_NAME;
+
+const COMMANDS: [&str; 14] = [
+    "protocol_version",
+    "name",
+    "version",
+    "known_command",
+    "list_commands",
+    "quit",
+    "boardsize",
+    "clear_board",
+    "play",
+    "genmove",
+    "undo",
+    "list_legal",
+    "showboard",
+    "final_score",
+];
+
+// answer of a command: its text, or an error message
+type Answer = Result<String, String>;
+
+fn parse_color(text: &str) -> Result<Color, String> {
+    match text.to_lowercase().as_str() {
+        "b" | "black" => Ok(Color::Black),
+        "w" | "white" => Ok(Color::White),
+        _ => Err(format!("invalid color '{}'", text)),
+    }
+}
+
+fn move_string(mv: Move) -> String {
+    match mv {
+        Move::Play(_) => mv.to_string(),
+        Move::Pass => "pass".to_string(),
+    }
+}
+
+/// The board with coordinates, black discs as `X` and white ones as `O`
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+/// use othlib::protocol::gtp::board_text;
+///
+/// let text = board_text(&Position::new());
+/// assert_eq!(text.lines().nth(4), Some("4 . . . O X . . . 4"));
+/// ```
+pub fn board_text(position: &Position) -> String {
+    let squares: Vec<char> = position.to_squares('X', 'O', '.').chars().collect();
+    let mut text = String::from("  A B C D E F G H\n");
+
+    for (row, line) in squares.chunks(8).enumerate() {
+        let line: Vec<String> = line.iter().map(|c| c.to_string()).collect();
+        text += &format!("{} {} {}\n", row + 1, line.join(" "), row + 1);
+    }
+    text += "  A B C D E F G H\n";
+    text += match position.side {
+        Color::Black => "black to move",
+        Color::White => "white to move",
+    };
+
+    text
+}
+
+/// A GTP session
+pub struct Gtp {
+    engine: Engine,
+    game: GameRecord,
+    /// limits of the searches of `genmove`
+    pub limits: SearchLimits,
+}
+
+impl Gtp {
+    pub fn new(engine: Engine, limits: SearchLimits) -> Self {
+        Gtp {
+            engine,
+            game: GameRecord::default(),
+            limits,
+        }
+    }
+
+    /// The game played so far
+    pub fn game(&self) -> &GameRecord {
+        &self.game
+    }
+
+    /// Read commands until `quit` or the end of the input
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::limits::SearchLimits;
+    /// use othlib::engine::search::Engine;
+    /// use othlib::protocol::gtp::Gtp;
+    ///
+    /// let input = "1 boardsize 10\n\
+    ///     play black f5\n\
+    ///     list_legal white\n\
+    ///     genmove white\n\
+    ///     undo\n\
+    ///     play white f4\n\
+    ///     play white f6\n\
+    ///     final_score\n\
+    ///     quit\n\
+    ///     play black e3\n";
+    ///
+    /// let mut session = Gtp::new(Engine::new(Box::new(MobilityEvaluator)), SearchLimits::depth(2));
+    /// let mut output = Vec::new();
+    /// session.run(input.as_bytes(), &mut output).unwrap();
+    ///
+    /// let output = String::from_utf8(output).unwrap();
+    /// let answers: Vec<&str> = output.split("\n\n").collect();
+    /// assert_eq!(answers[0], "?1 unsupported board size");
+    /// assert_eq!(answers[1], "=");
+    /// assert_eq!(answers[2], "= F4 D6 F6");
+    /// assert!(answers[3].starts_with("= "));
+    /// assert_eq!(answers[6], "? white can't play now");
+    /// assert_eq!(answers[7], "= 0");
+    ///
+    /// // the session ended with quit
+    /// assert_eq!(session.game().moves.len(), 2);
+    ///
+    /// // no move once the game is over
+    /// let input = "clear_board\n\
+    ///     play b f5\nplay w f6\nplay b f7\nplay w g5\nplay b h4\n\
+    ///     play w c5\nplay b b5\nplay w f4\nplay b e3\n\
+    ///     genmove white\n";
+    /// let mut output = Vec::new();
+    /// session.run(input.as_bytes(), &mut output).unwrap();
+    /// let output = String::from_utf8(output).unwrap();
+    /// assert_eq!(output.split("\n\n").nth(10), Some("? game over"));
+    /// assert_eq!(session.game().moves.len(), 9);
+    /// ```
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        for line in input.lines() {
+            if !self.handle(&line?, output)? {
+                break;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Execute a command and write its answer. Returns false when the session is over.
+    pub fn handle<W: Write>(&mut self, line: &str, output: &mut W) -> io::Result<bool> {
+        let mut words = line.split_whitespace().peekable();
+
+        // optional id
+        let id = match words.peek() {
+            Some(word) if word.chars().all(|c| c.is_ascii_digit()) => words.next(),
+            _ => None,
+        };
+        let command = match words.next() {
+            Some(command) => command,
+            None => return Ok(true),
+        };
+        let args: Vec<&str> = words.collect();
+
+        let answer = self.execute(command, &args);
+        let (prefix, text) = match answer {
+            Ok(text) => ('=', text),
+            Err(text) => ('?', text),
+        };
+        let separator = if text.is_empty() { "" } else { " " };
+        write!(
+            output,
+            "{}{}{}{}\n\n",
+            prefix,
+            id.unwrap_or(""),
+            separator,
+            text
+        )?;
+
+        Ok(command != "quit")
+    }
+
+    fn execute(&mut self, command: &str, args: &[&str]) -> Answer {
+        match command {
+            "protocol_version" => Ok("2".to_string()),
+            "name" => Ok(ENGINE_NAME.to_string()),
+            "version" => Ok(env!("CARGO_PKG_VERSION").to_string()),
+            "known_command" => Ok(args
+                .first()
+                .is_some_and(|name| COMMANDS.contains(name))
+                .to_string()),
+            "list_commands" => Ok(COMMANDS.join("\n")),
+            "quit" => Ok(String::new()),
+            "boardsize" => match args.first() {
+                Some(&"8") => Ok(String::new()),
+                _ => Err("unsupported board size".to_string()),
+            },
+            "clear_board" => {
+                self.game = GameRecord::default();
+                Ok(String::new())
+            }
+            "play" => self.play(args),
+            "genmove" => self.genmove(args),
+            "undo" => match self.game.moves.pop() {
+                Some(_) => Ok(String::new()),
+                None => Err("cannot undo".to_string()),
+            },
+            "list_legal" => self.list_legal(args),
+            "showboard" => Ok(format!("\n{}", board_text(&self.game.final_position()))),
+            "final_score" => Ok(self.final_score()),
+            _ => Err("unknown command".to_string()),
+        }
+    }
+
+    // check a color may play now, passing for the other one if it has no move
+    fn turn(&mut self, color: Color) -> Result<Position, String> {
+        let position = self.game.final_position();
+        if position.side == color {
+            return Ok(position);
+        }
+        if position.must_pass() && !position.is_game_over() {
+            self.game
+                .push_move(RecordedMove::new(Move::Pass))
+                .map_err(|e| e.to_string())?;
+            return Ok(self.game.final_position());
+        }
+
+        let name = match color {
+            Color::Black => "black",
+            Color::White => "white",
+        };
+        Err(format!("{} can't play now", name))
+    }
+
+    fn play(&mut self, args: &[&str]) -> Answer {
+        if args.len() != 2 {
+            return Err("syntax error".to_string());
+        }
+        let color = parse_color(args[0])?;
+        let mv: Move = args[1]
+            .parse()
+            .map_err(|_| format!("invalid move '{}'", args[1]))?;
+
+        let position = self.turn(color)?;
+        if !position.is_legal(mv) {
+            return Err("illegal move".to_string());
+        }
+        self.game
+            .push_move(RecordedMove::new(mv))
+            .map_err(|e| e.to_string())?;
+
+        Ok(String::new())
+    }
+
+    fn genmove(&mut self, args: &[&str]) -> Answer {
+        let color = parse_color(args.first().ok_or_else(|| "syntax error".to_string())?)?;
+        if self.game.final_position().is_game_over() {
+            return Err("game over".to_string());
+        }
+        let position = self.turn(color)?;
+
+        let result = self.engine.search(&position, &self.limits, false);
+        let mut rec = RecordedMove::new(result.best_move);
+        rec.eval = Some(result.score);
+        rec.time = Some(result.time.as_secs_f32());
+        self.game.push_move(rec).map_err(|e| e.to_string())?;
+
+        Ok(move_string(result.best_move))
+    }
+
+    fn list_legal(&self, args: &[&str]) -> Answer {
+        let position = self.game.final_position();
+        if let Some(color) = args.first() {
+            if parse_color(color)? != position.side {
+                return Ok(String::new());
+            }
+        }
+
+        let mut moves = position.legal_moves().bits;
+        let mut list = Vec::new();
+        while moves != 0 {
+            // bit 63 is A1, so the highest bits come first
+            let square = 63 - moves.leading_zeros() as usize;
+            list.push(Move::Play(square).to_string());
+            moves &= !(1u64 << square);
+        }
+
+        Ok(list.join(" "))
+    }
+
+    fn final_score(&self) -> String {
+        let score = self.game.final_position().final_score();
+        match score {
+            s if s > 0 => format!("B+{}", s),
+            s if s < 0 => format!("W+{}", -s),
+            _ => "0".to_string(),
+        }
+    }
+}
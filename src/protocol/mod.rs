@@ -0,0 +1,7 @@
+// Text protocols to drive the engine from GUIs and scripts
+
+pub mod gtp;
+pub mod nboard;
+
+/// Name given by the engine to GUIs and match runners
+pub const ENGINE_NAME: &str = "othlib";
@@ -0,0 +1,226 @@
+// NBoard engine protocol, as spoken by the NBoard GUI and by tournament managers.
+//
+// The GUI sends one command per line, and the engine answers:
+//
+// * `nboard <version>`: start of the session, answered by `set myname <name>`
+// * `set depth <n>`: search depth of the following searches
+// * `set game <ggf>`: the game to play from, in GGF
+// * `move <move>[/<eval>/<time>]`: a move was played
+// * `go`: the engine plays, answering `=== <move>/<eval>/<time>`
+// * `hint <n>`: the engine analyzes the `n` best moves, answering `search <pv> <eval> 0 <depth>`
+//   for each one. Depth is `100%` for exact scores.
+// * `learn`: the game is over and can be learnt, answered by `learned`
+// * `ping <n>`: answered by `pong <n>` once all previous commands are done
+// * `quit`
+//
+// Searches are answered between `status <text>` lines, and errors are reported in a status line.
+// Other commands are ignored.
+
+use std::io::{self, BufRead, Write};
+
+use crate::board::notation::{moves_to_string, Case};
+use crate::board::position::Move;
+use crate::engine::limits::SearchLimits;
+use crate::engine::search::{Engine, SearchResult};
+use crate::game::record::{GameRecord, RecordedMove};
+use crate::protocol::ENGINE_NAME;
+
+// depth used until the GUI sets one
+const DEFAULT_DEPTH: u32 = 10;
+
+/// An NBoard session
+pub struct NBoard {
+    engine: Engine,
+    game: GameRecord,
+    depth: u32,
+}
+
+// depth shown to the GUI
+fn depth_string(result: &SearchResult) -> String {
+    if result.exact {
+        "100%".to_string()
+    } else {
+        result.depth.to_string()
+    }
+}
+
+impl NBoard {
+    pub fn new(engine: Engine) -> Self {
+        NBoard {
+            engine,
+            game: GameRecord::default(),
+            depth: DEFAULT_DEPTH,
+        }
+    }
+
+    /// The game played so far
+    pub fn game(&self) -> &GameRecord {
+        &self.game
+    }
+
+    /// Read commands until `quit` or the end of the input
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::engine::eval::MobilityEvaluator;
+    /// use othlib::engine::search::Engine;
+    /// use othlib::protocol::nboard::NBoard;
+    ///
+    /// let input = "nboard 2\n\
+    ///     set depth 2\n\
+    ///     set game (;GM[Othello]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[f5];)\n\
+    ///     ping 1\n\
+    ///     move d6/0.5/1.2\n\
+    ///     go\n\
+    ///     hint 2\n\
+    ///     move a1\n\
+    ///     learn\n";
+    ///
+    /// let mut session = NBoard::new(Engine::new(Box::new(MobilityEvaluator)));
+    /// let mut output = Vec::new();
+    /// session.run(input.as_bytes(), &mut output).unwrap();
+    ///
+    /// let output = String::from_utf8(output).unwrap();
+    /// let lines: Vec<&str> = output.lines().collect();
+    /// assert_eq!(lines[0], "set myname othlib");
+    /// assert_eq!(lines[1], "pong 1");
+    /// assert!(lines[3].starts_with("=== "));
+    /// assert_eq!(lines.iter().filter(|l| l.starts_with("search ")).count(), 2);
+    /// assert!(output.contains("status illegal move"));
+    /// assert_eq!(lines.last(), Some(&"learned"));
+    ///
+    /// // f5, d6 and the engine move
+    /// assert_eq!(session.game().moves.len(), 3);
+    ///
+    /// // no move once the game is over
+    /// let input = "set game (;GM[Othello]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[f5]W[f6]B[f7]W[g5]B[h4]W[c5]B[b5]W[f4]B[e3];)\n\
+    ///     go\n";
+    /// let mut output = Vec::new();
+    /// session.run(input.as_bytes(), &mut output).unwrap();
+    /// assert_eq!(String::from_utf8(output).unwrap(), "status game over\n");
+    /// ```
+    pub fn run<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+        for line in input.lines() {
+            if !self.handle(&line?, output)? {
+                break;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Execute a command. Returns false when the session is over.
+    pub fn handle<W: Write>(&mut self, line: &str, output: &mut W) -> io::Result<bool> {
+        let line = line.trim();
+        let (command, args) = match line.find(char::is_whitespace) {
+            Some(i) => (&line[..i], line[i..].trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "nboard" => writeln!(output, "set myname {}", ENGINE_NAME)?,
+            "set" => self.set(args, output)?,
+            "move" => self.play(args, output)?,
+            "go" => self.go(output)?,
+            "hint" => match args.parse() {
+                Ok(n) => self.hint(n, output)?,
+                Err(_) => writeln!(output, "status invalid hint count '{}'", args)?,
+            },
+            "learn" => writeln!(output, "learned")?,
+            "ping" => writeln!(output, "pong {}", args)?,
+            "quit" => return Ok(false),
+            _ => (),
+        }
+
+        Ok(true)
+    }
+
+    fn set<W: Write>(&mut self, args: &str, output: &mut W) -> io::Result<()> {
+        let (name, value) = match args.find(char::is_whitespace) {
+            Some(i) => (&args[..i], args[i..].trim()),
+            None => (args, ""),
+        };
+
+        match name {
+            "depth" => match value.parse() {
+                Ok(depth) if depth > 0 => self.depth = depth,
+                _ => writeln!(output, "status invalid depth '{}'", value)?,
+            },
+            "game" => match GameRecord::from_ggf(value) {
+                Ok(game) => self.game = game,
+                Err(e) => writeln!(output, "status {}", e)?,
+            },
+            _ => (),
+        }
+
+        Ok(())
+    }
+
+    // a move of the GUI, with an optional evaluation and time
+    fn play<W: Write>(&mut self, args: &str, output: &mut W) -> io::Result<()> {
+        let mut fields = args.split('/');
+        let mv: Move = match fields.next().unwrap_or("").parse() {
+            Ok(mv) => mv,
+            Err(_) => return writeln!(output, "status invalid move '{}'", args),
+        };
+
+        let mut rec = RecordedMove::new(mv);
+        rec.eval = fields.next().and_then(|eval| eval.parse().ok());
+        rec.time = fields.next().and_then(|time| time.parse().ok());
+
+        if self.game.push_move(rec).is_err() {
+            writeln!(output, "status illegal move '{}'", args)?;
+        }
+        Ok(())
+    }
+
+    fn go<W: Write>(&mut self, output: &mut W) -> io::Result<()> {
+        let position = self.game.final_position();
+        if position.is_game_over() {
+            return writeln!(output, "status game over");
+        }
+
+        writeln!(output, "status thinking")?;
+        let result = self
+            .engine
+            .search(&position, &SearchLimits::depth(self.depth), false);
+
+        let mut rec = RecordedMove::new(result.best_move);
+        rec.eval = Some(result.score);
+        rec.time = Some(result.time.as_secs_f32());
+        if let Err(e) = self.game.push_move(rec) {
+            return writeln!(output, "status {}", e);
+        }
+
+        writeln!(
+            output,
+            "=== {}/{:.2}/{:.2}",
+            result.best_move,
+            result.score,
+            result.time.as_secs_f64()
+        )?;
+        writeln!(output, "status")
+    }
+
+    fn hint<W: Write>(&mut self, n: usize, output: &mut W) -> io::Result<()> {
+        writeln!(output, "status analyzing")?;
+
+        let position = self.game.final_position();
+        if !position.is_game_over() {
+            let results = self
+                .engine
+                .search_moves(&position, &SearchLimits::depth(self.depth));
+            for result in results.iter().take(n) {
+                writeln!(
+                    output,
+                    "search {} {:.2} 0 {}",
+                    moves_to_string(&result.pv, "-", Case::Upper),
+                    result.score,
+                    depth_string(result)
+                )?;
+            }
+        }
+
+        writeln!(output, "status")
+    }
+}
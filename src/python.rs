@@ -0,0 +1,591 @@
+// Python bindings, built with the `python` feature into an `othlib` extension module. maturin
+// enables the feature from pyproject.toml:
+//
+//     maturin develop
+//
+// `BitBoard` has the operators of the former pure Python package (`&`, `|`, `^`, `~`, shifts, and
+// `@` to shift in a direction), and the module adds positions, games, the WTHOR readers and SVG
+// boards, which Jupyter displays directly. Moves and squares are strings in algebric notation,
+// and errors are raised as `ValueError` or `IOError`.
+
+use std::fmt::Display;
+use std::fs;
+use std::io;
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyIterator, PyList};
+
+use crate::board::bitboard::BitBoard;
+use crate::board::color::Color;
+use crate::board::direction::{Direction, DIRECTIONS};
+use crate::board::notation::{self, moves_to_string, Case};
+use crate::board::position::{Move, Position};
+use crate::game::record::{GameRecord, RecordedMove};
+use crate::game::wthor::{WThorFile, WThorGame, WThorPlayer, WThorTournament, WThorable};
+use crate::protocol::gtp::board_text;
+use crate::svg::board_style::BoardStyle;
+use crate::svg::svg_board::SVGBoard;
+
+fn value_error<E: Display>(e: E) -> PyErr {
+    PyValueError::new_err(e.to_string())
+}
+
+fn parse_move(mv: &str) -> PyResult<Move> {
+    mv.parse().map_err(value_error)
+}
+
+fn parse_direction(dir: &str) -> PyResult<Direction> {
+    match dir {
+        "N" => Ok(Direction::N),
+        "NE" => Ok(Direction::NE),
+        "E" => Ok(Direction::E),
+        "SE" => Ok(Direction::SE),
+        "S" => Ok(Direction::S),
+        "SW" => Ok(Direction::SW),
+        "W" => Ok(Direction::W),
+        "NW" => Ok(Direction::NW),
+        _ => Err(PyValueError::new_err(format!(
+            "Direction {} is unknown !",
+            dir
+        ))),
+    }
+}
+
+// squares of a bitboard, from A1 to H8
+fn move_list(mut bits: u64) -> Vec<String> {
+    let mut list = Vec::new();
+    while bits != 0 {
+        let square = 63 - bits.leading_zeros() as usize;
+        list.push(Move::Play(square).to_string());
+        bits &= !(1u64 << square);
+    }
+    list
+}
+
+/// Color of the discs: `Color.black` or `Color.white`
+#[pyclass(name = "Color", eq, eq_int, frozen, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PyColor {
+    #[pyo3(name = "black")]
+    Black,
+    #[pyo3(name = "white")]
+    White,
+}
+
+impl From<Color> for PyColor {
+    fn from(color: Color) -> Self {
+        match color {
+            Color::Black => PyColor::Black,
+            Color::White => PyColor::White,
+        }
+    }
+}
+
+impl From<PyColor> for Color {
+    fn from(color: PyColor) -> Self {
+        match color {
+            PyColor::Black => Color::Black,
+            PyColor::White => Color::White,
+        }
+    }
+}
+
+/// A 64-bit set of squares, bit 63 being A1 and bit 0 H8
+#[pyclass(name = "BitBoard", eq, hash, frozen, from_py_object)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PyBitBoard(BitBoard);
+
+#[pymethods]
+impl PyBitBoard {
+    #[new]
+    fn new(bits: u64) -> Self {
+        PyBitBoard(BitBoard::new(bits))
+    }
+
+    /// Build a bitboard from a list of algebric coordinates
+    #[staticmethod]
+    fn from_algebric_list(algs: Vec<String>) -> PyResult<Self> {
+        let mut bits = 0u64;
+        for alg in &algs {
+            bits |= 1u64 << notation::algebric_to_square(alg).map_err(value_error)?;
+        }
+        Ok(PyBitBoard(BitBoard::new(bits)))
+    }
+
+    #[staticmethod]
+    fn zero() -> Self {
+        PyBitBoard(BitBoard::zero())
+    }
+
+    #[staticmethod]
+    fn max_value() -> Self {
+        PyBitBoard(BitBoard::max_value())
+    }
+
+    #[getter]
+    fn bits(&self) -> u64 {
+        self.0.bits
+    }
+
+    fn __int__(&self) -> u64 {
+        self.0.bits
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BitBoard(0x{:016x})", self.0.bits)
+    }
+
+    fn __invert__(&self) -> Self {
+        PyBitBoard(BitBoard::new(!self.0.bits))
+    }
+
+    fn __and__(&self, other: PyBitBoard) -> Self {
+        PyBitBoard(BitBoard::new(self.0.bits & other.0.bits))
+    }
+
+    fn __or__(&self, other: PyBitBoard) -> Self {
+        PyBitBoard(BitBoard::new(self.0.bits | other.0.bits))
+    }
+
+    fn __xor__(&self, other: PyBitBoard) -> Self {
+        PyBitBoard(BitBoard::new(self.0.bits ^ other.0.bits))
+    }
+
+    /// Left shift, a negative shift going right. Bits shifted out are lost.
+    fn __lshift__(&self, n: i64) -> Self {
+        let bits = match n {
+            n if n <= -64 || n >= 64 => 0,
+            n if n < 0 => self.0.bits >> -n,
+            n => self.0.bits << n,
+        };
+        PyBitBoard(BitBoard::new(bits))
+    }
+
+    /// Right shift, a negative shift going left
+    fn __rshift__(&self, n: i64) -> Self {
+        self.__lshift__(-n)
+    }
+
+    /// The 64 bits as 0 or 1, from A1 to H8
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, Vec::<usize>::from(&self.0))?.try_iter()
+    }
+
+    /// Shift all bits by one square in a direction (`N`, `NE`, ... `NW`), e.g. `bitboard @ 'N'`
+    fn __matmul__(&self, dir: &str) -> PyResult<Self> {
+        Ok(PyBitBoard(self.0.shr(&parse_direction(dir)?)))
+    }
+
+    /// The 8 bits of a row, 0 being the first one
+    fn row(&self, row: usize) -> PyResult<Vec<usize>> {
+        if row > 7 {
+            return Err(PyValueError::new_err(format!(
+                "Row number {} is wrong !",
+                row
+            )));
+        }
+        Ok(self.0.row(row))
+    }
+
+    /// The 8 bits of a column, 0 being column A
+    fn col(&self, col: usize) -> PyResult<Vec<usize>> {
+        if col > 7 {
+            return Err(PyValueError::new_err(format!(
+                "Col number {} is wrong !",
+                col
+            )));
+        }
+        Ok(self.0.col(col))
+    }
+
+    /// Indexes from A1 (0) to H8 (63) of the bits equal to `zero_or_one`
+    fn bit_list(&self, zero_or_one: &str) -> Vec<usize> {
+        self.0
+            .to_string()
+            .chars()
+            .enumerate()
+            .filter(|(_, c)| c.to_string() == zero_or_one)
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Squares of the set bits, e.g. `['D5', 'E4']`
+    fn squares(&self) -> Vec<String> {
+        Vec::<String>::from(&self.0)
+    }
+
+    fn count(&self) -> u32 {
+        self.0.bits.count_ones()
+    }
+
+    /// Squares adjacent in a direction, or in all of them
+    #[pyo3(signature = (dir = None))]
+    fn adjacent(&self, dir: Option<&str>) -> PyResult<Self> {
+        let bits = match dir {
+            Some(dir) => self.0.shr(&parse_direction(dir)?).bits,
+            None => DIRECTIONS
+                .iter()
+                .fold(0, |acc, dir| acc | self.0.shr(dir).bits),
+        };
+        Ok(PyBitBoard(BitBoard::new(bits)))
+    }
+
+    /// The 8 rows of bits, one per line
+    #[pyo3(name = "to_ascii")]
+    fn ascii(&self) -> String {
+        let bits = self.0.to_string();
+        let rows: Vec<&str> = (0..8).map(|i| &bits[8 * i..8 * (i + 1)]).collect();
+        rows.join("\n")
+    }
+
+    /// Legal moves of these discs against the opponent ones
+    fn possible_moves(&self, opponent: PyBitBoard) -> Self {
+        PyBitBoard(self.0.line_cap_moves(&opponent.0))
+    }
+
+    /// Opponent discs flipped by playing on a square
+    fn flips(&self, opponent: PyBitBoard, square: &str) -> PyResult<Self> {
+        let square = notation::algebric_to_square(square).map_err(value_error)?;
+        Ok(PyBitBoard(self.0.flips(&opponent.0, square)))
+    }
+}
+
+/// Discs of both colors and the color to move
+#[pyclass(name = "Position", eq, from_py_object)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct PyPosition(Position);
+
+#[pymethods]
+impl PyPosition {
+    /// The starting position
+    #[new]
+    fn new() -> Self {
+        PyPosition(Position::new())
+    }
+
+    /// A position from its 64 squares from A1 to H8: `X` or `*` for black, `O` for white and
+    /// `-` or `.` for empty squares
+    #[staticmethod]
+    fn from_squares(squares: &str, side: PyColor) -> PyResult<Self> {
+        Position::from_squares(squares, side.into())
+            .map(PyPosition)
+            .ok_or_else(|| PyValueError::new_err("invalid squares"))
+    }
+
+    #[getter]
+    fn black(&self) -> PyBitBoard {
+        PyBitBoard(self.0.black)
+    }
+
+    #[getter]
+    fn white(&self) -> PyBitBoard {
+        PyBitBoard(self.0.white)
+    }
+
+    /// Color to move
+    #[getter]
+    fn side(&self) -> PyColor {
+        self.0.side.into()
+    }
+
+    /// Discs of the color to move
+    fn player(&self) -> PyBitBoard {
+        PyBitBoard(self.0.player())
+    }
+
+    fn opponent(&self) -> PyBitBoard {
+        PyBitBoard(self.0.opponent())
+    }
+
+    fn count(&self, color: PyColor) -> u32 {
+        self.0.count(color.into())
+    }
+
+    fn nb_empty(&self) -> u32 {
+        self.0.nb_empty()
+    }
+
+    fn legal_moves(&self) -> PyBitBoard {
+        PyBitBoard(self.0.legal_moves())
+    }
+
+    /// Legal moves of the color to move, e.g. `['D3', 'C4', 'F5', 'E6']`
+    fn legal_move_list(&self) -> Vec<String> {
+        move_list(self.0.legal_moves().bits)
+    }
+
+    fn is_legal(&self, mv: &str) -> PyResult<bool> {
+        Ok(self.0.is_legal(parse_move(mv)?))
+    }
+
+    /// Play a move, `pass` being a pass, and return the flipped discs
+    fn play(&mut self, mv: &str) -> PyResult<PyBitBoard> {
+        let flipped = self.0.play(parse_move(mv)?).map_err(value_error)?;
+        Ok(PyBitBoard(flipped))
+    }
+
+    fn must_pass(&self) -> bool {
+        self.0.must_pass()
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.0.is_game_over()
+    }
+
+    /// Final disc differential for black, empty squares going to the winner
+    fn final_score(&self) -> i32 {
+        self.0.final_score()
+    }
+
+    #[pyo3(signature = (black = 'X', white = 'O', empty = '-'))]
+    fn to_squares(&self, black: char, white: char, empty: char) -> String {
+        self.0.to_squares(black, white, empty)
+    }
+
+    fn __str__(&self) -> String {
+        board_text(&self.0)
+    }
+
+    fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    /// The position as an SVG document
+    fn svg(&self) -> String {
+        let mut svg = SVGBoard::new();
+        svg.draw_position(&self.0);
+        svg.close();
+        svg.get_tags().to_string()
+    }
+
+    fn _repr_svg_(&self) -> String {
+        self.svg()
+    }
+}
+
+/// A game from a starting position, with its players and result
+#[pyclass(name = "Game", skip_from_py_object)]
+#[derive(Debug, Clone)]
+pub struct PyGame(GameRecord);
+
+#[pymethods]
+impl PyGame {
+    /// An empty game from the standard position
+    #[new]
+    fn new() -> Self {
+        PyGame(GameRecord::default())
+    }
+
+    /// A game from a list of moves, e.g. `f5d6c3`
+    #[staticmethod]
+    fn from_moves(moves: &str) -> PyResult<Self> {
+        GameRecord::from_move_string(moves)
+            .map(PyGame)
+            .map_err(value_error)
+    }
+
+    #[staticmethod]
+    fn from_ggf(ggf: &str) -> PyResult<Self> {
+        GameRecord::from_ggf(ggf).map(PyGame).map_err(value_error)
+    }
+
+    fn to_ggf(&self) -> String {
+        self.0.to_ggf()
+    }
+
+    #[getter]
+    fn black_player(&self) -> String {
+        self.0.black_player.clone()
+    }
+
+    #[getter]
+    fn white_player(&self) -> String {
+        self.0.white_player.clone()
+    }
+
+    /// Disc differential for black, if known
+    #[getter]
+    fn result(&self) -> Option<f32> {
+        self.0.result
+    }
+
+    #[getter]
+    fn theoretical_score(&self) -> Option<u8> {
+        self.0.theoretical_score
+    }
+
+    /// Moves played, passes included
+    #[getter]
+    fn moves(&self) -> Vec<String> {
+        self.0.moves.iter().map(|rec| rec.mv.to_string()).collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.0.moves.len()
+    }
+
+    fn __str__(&self) -> String {
+        let moves: Vec<Move> = self.0.moves.iter().map(|rec| rec.mv).collect();
+        moves_to_string(&moves, "", Case::Lower)
+    }
+
+    /// Play a move. A color with no legal move passes automatically.
+    fn play(&mut self, mv: &str) -> PyResult<()> {
+        self.0
+            .push_move(RecordedMove::new(parse_move(mv)?))
+            .map_err(value_error)
+    }
+
+    /// Take back the last move, and return it
+    fn undo(&mut self) -> Option<String> {
+        self.0.moves.pop().map(|rec| rec.mv.to_string())
+    }
+
+    /// The position before the move at `ply`, 0 being the starting position
+    fn position_at(&self, ply: usize) -> PyPosition {
+        PyPosition(self.0.position_at(ply))
+    }
+
+    fn final_position(&self) -> PyPosition {
+        PyPosition(self.0.final_position())
+    }
+}
+
+// records of a WTHOR file, IOError if it can't be opened and ValueError if it's truncated
+fn read_wthor<T: WThorable<T>>(path: &str) -> PyResult<Vec<T>> {
+    WThorFile::<T>::new(path)
+        .map(|file| file.data)
+        .map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => PyValueError::new_err(format!("{}: {}", path, e)),
+            _ => PyIOError::new_err(format!("{}: {}", path, e)),
+        })
+}
+
+/// Games of a WTHOR game file (.wtb)
+#[pyfunction]
+fn read_wthor_games(path: &str) -> PyResult<Vec<PyGame>> {
+    read_wthor::<WThorGame>(path)?
+        .iter()
+        .enumerate()
+        .map(|(i, game)| {
+            GameRecord::from_wthor(game)
+                .map(PyGame)
+                .map_err(|e| PyValueError::new_err(format!("{}: game #{}: {}", path, i + 1, e)))
+        })
+        .collect()
+}
+
+/// Player names of a WTHOR players file (WTHOR.JOU)
+#[pyfunction]
+fn read_wthor_players(path: &str) -> PyResult<Vec<String>> {
+    Ok(read_wthor::<WThorPlayer>(path)?
+        .into_iter()
+        .map(|p| p.player)
+        .collect())
+}
+
+/// Tournament names of a WTHOR tournaments file (WTHOR.TRN)
+#[pyfunction]
+fn read_wthor_tournaments(path: &str) -> PyResult<Vec<String>> {
+    Ok(read_wthor::<WThorTournament>(path)?
+        .into_iter()
+        .map(|t| t.tournament)
+        .collect())
+}
+
+/// An SVG drawing of a board
+#[pyclass(name = "SVGBoard", skip_from_py_object)]
+pub struct PySvgBoard {
+    board: SVGBoard,
+    closed: bool,
+}
+
+#[pymethods]
+impl PySvgBoard {
+    /// An empty board with coordinates, `size` pixels wide
+    #[new]
+    #[pyo3(signature = (size = 600.0))]
+    fn new(size: f32) -> Self {
+        let style = BoardStyle {
+            size,
+            ..BoardStyle::default()
+        };
+        PySvgBoard {
+            board: SVGBoard::with_style(style),
+            closed: false,
+        }
+    }
+
+    fn draw_legend(&mut self, legend: &str) {
+        self.board.draw_legend(legend);
+    }
+
+    fn draw_bit_indexes(&mut self) {
+        self.board.draw_bit_indexes();
+    }
+
+    fn draw_pieces_from_bitboard(&mut self, bitboard: PyBitBoard, color: PyColor) {
+        self.board
+            .draw_pieces_from_u64(bitboard.0.bits, color.into());
+    }
+
+    fn draw_piece_from_algebric(&mut self, square: &str, color: PyColor) -> PyResult<()> {
+        let square = notation::algebric_to_square(square).map_err(value_error)?;
+        self.board
+            .draw_pieces_from_u64(1u64 << square, color.into());
+        Ok(())
+    }
+
+    /// Highlight squares with a translucent color, like `"red"` or `"#ff0000"`
+    #[pyo3(signature = (bitboard, color = "red", opacity = 0.5))]
+    fn shade_squares(&mut self, bitboard: PyBitBoard, color: &str, opacity: f32) {
+        self.board.shade_squares(bitboard.0.bits, color, opacity);
+    }
+
+    fn draw_position(&mut self, position: PyPosition) {
+        self.board.draw_position(&position.0);
+    }
+
+    /// End the document: nothing can be drawn after
+    fn close(&mut self) {
+        if !self.closed {
+            self.board.close();
+            self.closed = true;
+        }
+    }
+
+    /// The SVG document, closed if it wasn't yet
+    #[getter]
+    fn tags(&mut self) -> String {
+        self.close();
+        self.board.get_tags().to_string()
+    }
+
+    fn write(&mut self, path: &str) -> PyResult<()> {
+        let tags = self.tags();
+        fs::write(path, tags).map_err(|e| PyIOError::new_err(format!("{}: {}", path, e)))
+    }
+
+    fn _repr_svg_(&mut self) -> String {
+        self.tags()
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "othlib")]
+fn python_module(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyColor>()?;
+    m.add_class::<PyBitBoard>()?;
+    m.add_class::<PyPosition>()?;
+    m.add_class::<PyGame>()?;
+    m.add_class::<PySvgBoard>()?;
+    m.add_function(wrap_pyfunction!(read_wthor_games, m)?)?;
+    m.add_function(wrap_pyfunction!(read_wthor_players, m)?)?;
+    m.add_function(wrap_pyfunction!(read_wthor_tournaments, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,86 @@
+// Look of an SVG board: colors, sizes, coordinates and fonts.
+//
+// Coordinates are in view box units, where a square is 1 wide and the board goes from 1 to 9,
+// leaving room for the coordinate labels around it.
+
+/// Where the coordinate labels (A-H and 1-8) are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordinatePlacement {
+    /// no labels
+    Hidden,
+    /// letters above the board, numbers on its left
+    TopLeft,
+    /// letters below the board, numbers on its right
+    BottomRight,
+    /// labels on the four sides
+    AllSides,
+}
+
+/// Colors, sizes and decorations of an SVG board. Any SVG color can be used, like `black`,
+/// `#009000` or `rgb(0,144,0)`.
+///
+/// # Examples
+/// ```
+/// use othlib::board::position::Position;
+/// use othlib::svg::board_style::{BoardStyle, CoordinatePlacement};
+/// use othlib::svg::svg_board::SVGBoard;
+///
+/// let style = BoardStyle {
+///     size: 300f32,
+///     board_color: String::from("#2e8b57"),
+///     coordinates: CoordinatePlacement::Hidden,
+///     star_points: true,
+///     ..BoardStyle::default()
+/// };
+///
+/// let svg = SVGBoard::with_style(style);
+/// assert!(svg.get_tags().contains(r#"width="300" height="300""#));
+/// assert!(svg.get_tags().contains("fill:#2e8b57"));
+/// assert_eq!(svg.get_tags().matches("<text").count(), 0);
+/// assert_eq!(svg.get_tags().matches("<circle").count(), 4);
+///
+/// // shading is drawn over each disc
+/// let mut svg = SVGBoard::with_style(BoardStyle { disc_shading: true, ..BoardStyle::default() });
+/// svg.draw_position(&Position::new());
+/// assert!(svg.get_tags().contains(r#"<radialGradient id="disc-shading""#));
+/// assert_eq!(svg.get_tags().matches("fill:url(#disc-shading)").count(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardStyle {
+    /// width and height of the SVG canvas, in pixels
+    pub size: f32,
+    pub board_color: String,
+    pub grid_color: String,
+    pub grid_width: f32,
+    pub black_disc_color: String,
+    pub white_disc_color: String,
+    /// radius of the discs, a square being 1 wide
+    pub disc_radius: f32,
+    /// shade discs with a radial gradient, to give them some relief
+    pub disc_shading: bool,
+    pub coordinates: CoordinatePlacement,
+    pub font_family: String,
+    /// color of the coordinates and of the legend
+    pub font_color: String,
+    /// dots at the corners of the C3-F6 central square, as on tournament boards
+    pub star_points: bool,
+}
+
+impl Default for BoardStyle {
+    fn default() -> Self {
+        BoardStyle {
+            size: 600f32,
+            board_color: String::from("#009000"),
+            grid_color: String::from("black"),
+            grid_width: 0.05f32,
+            black_disc_color: String::from("black"),
+            white_disc_color: String::from("white"),
+            disc_radius: 0.4f32,
+            disc_shading: false,
+            coordinates: CoordinatePlacement::TopLeft,
+            font_family: String::from("Verdana"),
+            font_color: String::from("black"),
+            star_points: false,
+        }
+    }
+}
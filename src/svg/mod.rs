@@ -1,3 +1,4 @@
+pub mod board_style;
 pub mod svg_board;
 pub mod svg_doc;
 pub mod vecint64;
@@ -1,67 +1,182 @@
 use crate::board::color::Color;
 use crate::board::coordinate::Coordinate;
+use crate::board::position::{IllegalMove, Move, Position};
 //use crate::board::point::Point;
+use crate::game::record::{GameRecord, RecordError};
+use crate::svg::board_style::{BoardStyle, CoordinatePlacement};
 use crate::svg::svg_doc::SVGDoc;
 use crate::svg::vecint64::VecInt64;
 
+// center of the square of a bit index in the view box, A1 being at (1.5, 1.5)
+fn square_center(square: usize) -> (f32, f32) {
+    let coord = Coordinate::to_bitboard(square);
+    ((coord.0 as f32) + 1.5f32, (coord.1 as f32) + 1.5f32)
+}
+
+// bit indexes of the squares of a bitboard
+fn squares_of(bits: u64) -> impl Iterator<Item = usize> {
+    (0..64).filter(move |square| bits & (1 << square) != 0)
+}
+
+// color of the disc on a square, if any
+fn square_color(position: &Position, square: usize) -> Option<Color> {
+    if position.black.bits & (1 << square) != 0 {
+        Some(Color::Black)
+    } else if position.white.bits & (1 << square) != 0 {
+        Some(Color::White)
+    } else {
+        None
+    }
+}
+
+// a replay holds the final position during this number of moves before starting over
+const REPLAY_HOLD_MOVES: f32 = 3f32;
+
+// shortest time between two moves of a replay, in seconds
+const REPLAY_MIN_MOVE_DURATION: f32 = 0.01f32;
+
 pub struct SVGBoard {
     svg_tags: String,
+    style: BoardStyle,
+    // vertical position of the legend, below the coordinates if any
+    legend_y: f32,
+}
+
+impl Default for SVGBoard {
+    fn default() -> Self {
+        SVGBoard::new()
+    }
 }
 
 impl SVGBoard {
+    /// An empty board with the default style
     pub fn new() -> SVGBoard {
-        let mut s = SVGDoc::new_document("0 0 10 10", 600f32, 600f32);
+        SVGBoard::with_style(BoardStyle::default())
+    }
 
-        // draw board background
-        s += &SVGDoc::rect((1f32, 1f32), 8f32, 8f32, "fill:#009000;");
-
-        // row headers
-        for row in 1..=8 {
-            s += &SVGDoc::text(
-                (0.4f32, (row as f32) + 0.6f32),
-                "font-family:Verdana;font-size:0.5px",
-                &row.to_string(),
+    /// An empty board drawn with a style. Coordinates below the board make it a bit higher, to
+    /// leave room for the legend.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::board_style::{BoardStyle, CoordinatePlacement};
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let style = BoardStyle {
+    ///     coordinates: CoordinatePlacement::AllSides,
+    ///     font_family: String::from("Georgia"),
+    ///     ..BoardStyle::default()
+    /// };
+    ///
+    /// let svg = SVGBoard::with_style(style);
+    /// assert!(svg.get_tags().contains(r#"viewBox="0 0 10 10.6" width="600" height="636""#));
+    /// assert_eq!(svg.get_tags().matches("font-family:Georgia").count(), 32);
+    /// ```
+    pub fn with_style(style: BoardStyle) -> SVGBoard {
+        let top_left = matches!(
+            style.coordinates,
+            CoordinatePlacement::TopLeft | CoordinatePlacement::AllSides
+        );
+        let bottom_right = matches!(
+            style.coordinates,
+            CoordinatePlacement::BottomRight | CoordinatePlacement::AllSides
+        );
+
+        let height = if bottom_right { 10.6f32 } else { 10f32 };
+        let mut s = SVGDoc::new_document(
+            &format!("0 0 10 {}", height),
+            style.size,
+            style.size * height / 10f32,
+        );
+
+        // disc shading is a gradient drawn over the discs, so it suits any disc color
+        if style.disc_shading {
+            s += &SVGDoc::radial_gradient(
+                "disc-shading",
+                &[
+                    (0f32, "white", 0.5f32),
+                    (0.6f32, "white", 0f32),
+                    (1f32, "black", 0.3f32),
+                ],
             );
         }
 
-        // col headers
+        // draw board background
+        s += &SVGDoc::rect(
+            (1f32, 1f32),
+            8f32,
+            8f32,
+            &format!("fill:{};", style.board_color),
+        );
+
+        // row and col headers
+        let font = format!(
+            "font-family:{};font-size:0.5px;fill:{}",
+            style.font_family, style.font_color
+        );
         for (i, col) in chars!('A'..='H').iter().enumerate() {
-            s += &SVGDoc::text(
-                ((i as f32) + 1.3f32, 0.8f32),
-                "font-family:Verdana;font-size:0.5px",
-                &col.to_string(),
-            );
+            let middle = (i as f32) + 1.5f32;
+            let (row, col) = ((i + 1).to_string(), col.to_string());
+
+            if top_left {
+                s += &SVGDoc::text_with_anchor((0.55f32, middle + 0.18f32), "middle", &font, &row);
+                s += &SVGDoc::text_with_anchor((middle, 0.8f32), "middle", &font, &col);
+            }
+            if bottom_right {
+                s += &SVGDoc::text_with_anchor((9.45f32, middle + 0.18f32), "middle", &font, &row);
+                s += &SVGDoc::text_with_anchor((middle, 9.6f32), "middle", &font, &col);
+            }
         }
 
-        // horizontal grid: first rule is different to match perfectly
-        s += &SVGDoc::line(
-            (1f32, 1.025f32),
-            (9f32, 1.025f32),
-            "stroke:black;stroke-width:0.05",
+        // grid: outer rules are moved inside by half their width to match the board perfectly
+        let grid = format!(
+            "stroke:{};stroke-width:{}",
+            style.grid_color, style.grid_width
         );
-        for row in 2..=9 {
-            s += &SVGDoc::line(
-                (1f32, row as f32),
-                (9f32, row as f32),
-                "stroke:black;stroke-width:0.05",
-            );
+        for i in 0..=8 {
+            let rule = match i {
+                0 => 1f32 + style.grid_width / 2f32,
+                8 => 9f32 - style.grid_width / 2f32,
+                _ => (i + 1) as f32,
+            };
+            s += &SVGDoc::line((1f32, rule), (9f32, rule), &grid);
+            s += &SVGDoc::line((rule, 1f32), (rule, 9f32), &grid);
         }
 
-        // vertical grid: last rule is different
-        for col in 1..=8 {
-            s += &SVGDoc::line(
-                (col as f32, 1f32),
-                (col as f32, 9f32),
-                "stroke:black;stroke-width:0.05",
-            );
+        if style.star_points {
+            for &point in [(3f32, 3f32), (7f32, 3f32), (3f32, 7f32), (7f32, 7f32)].iter() {
+                s += &SVGDoc::circle(point, 0.08f32, &format!("fill:{}", style.grid_color));
+            }
         }
-        s += &SVGDoc::line(
-            (8.975f32, 1f32),
-            (8.975f32, 9f32),
-            "stroke:black;stroke-width:0.05",
-        );
 
-        SVGBoard { svg_tags: s }
+        SVGBoard {
+            svg_tags: s,
+            style,
+            legend_y: if bottom_right { 10.3f32 } else { 9.6f32 },
+        }
+    }
+
+    /// Style of the board
+    pub fn style(&self) -> &BoardStyle {
+        &self.style
+    }
+
+    fn disc_color(&self, color: Color) -> &str {
+        match color {
+            Color::Black => &self.style.black_disc_color,
+            Color::White => &self.style.white_disc_color,
+        }
+    }
+
+    // a disc of a color, shaded or not
+    fn disc(&self, center: (f32, f32), color: Color) -> String {
+        let fill = format!("fill:{}", self.disc_color(color));
+
+        let mut disc = SVGDoc::circle(center, self.style.disc_radius, &fill);
+        if self.style.disc_shading {
+            disc += &SVGDoc::circle(center, self.style.disc_radius, "fill:url(#disc-shading)");
+        }
+        disc
     }
 
     /// Add SVG end tag </svg>
@@ -82,12 +197,11 @@ impl SVGBoard {
 
     /// Write a legend below the SVG board
     pub fn draw_legend(&mut self, legend: &str) {
-        self.svg_tags += &SVGDoc::text_with_anchor(
-            (5f32, 9.6f32),
-            "middle",
-            "font-family:Verdana;font-size:0.5px;fill:black",
-            legend,
+        let font = format!(
+            "font-family:{};font-size:0.5px;fill:{}",
+            self.style.font_family, self.style.font_color
         );
+        self.svg_tags += &SVGDoc::text_with_anchor((5f32, self.legend_y), "middle", &font, legend);
     }
 
     /// Add bit numbers in the Othello squares. 63 is the most significant bit and therefore is at (0,0) or A1
@@ -106,12 +220,6 @@ impl SVGBoard {
 
     /// Draw black or white piece using a bitboard (u64 int)
     pub fn draw_pieces_from_u64(&mut self, pieces: u64, color: Color) {
-        // get color
-        let svg_color = match color {
-            Color::Black => "black",
-            Color::White => "white",
-        };
-
         // convert binary digit to string
         let bits = format!("{:#066b}", pieces);
 
@@ -122,18 +230,347 @@ impl SVGBoard {
                 // calculate coordinates
                 let coord = Coordinate::to_bitboard(63 - i);
 
-                let style = format!("fill:{}", svg_color);
-
                 // draw piece as a circle
-                self.svg_tags += &SVGDoc::circle(
+                self.svg_tags += &self.disc(
                     ((coord.0 as f32) + 1.5f32, (coord.1 as f32) + 1.5f32),
-                    0.4f32,
-                    &style,
-                )
+                    color,
+                );
             }
         }
     }
 
+    /// Draw the discs of a position
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_position(&Position::new());
+    /// assert_eq!(svg.get_tags().matches("<circle").count(), 4);
+    /// ```
+    pub fn draw_position(&mut self, position: &Position) {
+        self.draw_pieces_from_u64(position.black.bits, Color::Black);
+        self.draw_pieces_from_u64(position.white.bits, Color::White);
+    }
+
+    /// Draw a game as in Othello books and magazines: the discs of the position after `ply`
+    /// moves, each disc played since the start being numbered in the contrasting color. Passes
+    /// are not numbered, so numbers go from 1 to the number of discs played.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let game = GameRecord::from_move_string("f5d6c3d3c4").unwrap();
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_game_at(&game, 3);
+    /// assert_eq!(svg.get_tags().matches("<circle").count(), 7);
+    /// assert_eq!(svg.get_tags().matches("font-size:0.35px").count(), 3);
+    ///
+    /// // F5 is black after C3, so its number is white
+    /// assert!(svg.get_tags().contains(r#"x="6.5" y="5.62" text-anchor="middle" style="font-family:Verdana;font-size:0.35px;font-weight:bold;fill:white">1<"#));
+    /// ```
+    pub fn draw_game_at(&mut self, game: &GameRecord, ply: usize) {
+        let position = game.position_at(ply);
+        self.draw_position(&position);
+
+        let squares = game.moves.iter().take(ply).filter_map(|rec| match rec.mv {
+            Move::Play(square) => Some(square),
+            Move::Pass => None,
+        });
+
+        for (i, square) in squares.enumerate() {
+            let center = square_center(square);
+            let number_color = if position.black.bits & (1 << square) != 0 {
+                &self.style.white_disc_color
+            } else {
+                &self.style.black_disc_color
+            };
+
+            self.svg_tags += &SVGDoc::text_with_anchor(
+                (center.0, center.1 + 0.12f32),
+                "middle",
+                &format!(
+                    "font-family:{};font-size:0.35px;font-weight:bold;fill:{}",
+                    self.style.font_family, number_color
+                ),
+                &(i + 1).to_string(),
+            );
+        }
+    }
+
+    /// Draw the final position of a game with move numbers, see `draw_game_at`
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::game::record::GameRecord;
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let game = GameRecord::from_move_string("f5d6c3d3c4").unwrap();
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_game(&game);
+    /// assert_eq!(svg.get_tags().matches("<circle").count(), 9);
+    /// assert_eq!(svg.get_tags().matches("font-size:0.35px").count(), 5);
+    /// ```
+    pub fn draw_game(&mut self, game: &GameRecord) {
+        self.draw_game_at(game, game.moves.len());
+    }
+
+    /// Draw an animated replay of a game, which loops forever with SMIL animations and no
+    /// script: discs appear and flip every `move_duration` seconds, then the final position is
+    /// held for a few moves before the replay starts over. Viewers without SMIL support show
+    /// the starting position. Durations below a hundredth of a second, or not a number, are
+    /// replaced by a hundredth of a second. Fails, drawing nothing, if a move of the game is
+    /// illegal, as games built field by field or deserialized are not validated.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Move;
+    /// use othlib::game::record::{GameRecord, RecordedMove};
+    /// use othlib::svg::board_style::BoardStyle;
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let game = GameRecord::from_move_string("f5d6c3").unwrap();
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_game_replay(&game, 0.5).unwrap();
+    ///
+    /// // F5 appears after the first half second, then nothing changes until the replay loops
+    /// assert!(svg.get_tags().contains(concat!(
+    ///     r#"<circle cx="6.5" cy="5.5" r="0.4" fill="none">"#,
+    ///     r#"<animate attributeName="fill" dur="3s" repeatCount="indefinite" calcMode="discrete" "#,
+    ///     r#"keyTimes="0;0.16666667" values="none;black" /></circle>"#
+    /// )));
+    ///
+    /// // F5, D6 and C3 are played, and E5, D5 and D4 flipped
+    /// assert_eq!(svg.get_tags().matches("<animate").count(), 6);
+    ///
+    /// // with shading, the overlay of F5 is hidden until it's played
+    /// let style = BoardStyle { disc_shading: true, ..BoardStyle::default() };
+    /// let mut svg = SVGBoard::with_style(style);
+    /// svg.draw_game_replay(&game, 0.0).unwrap();
+    /// assert!(svg.get_tags().contains(concat!(
+    ///     r#"<circle cx="6.5" cy="5.5" r="0.4" fill="url(#disc-shading)" opacity="0">"#,
+    ///     r#"<animate attributeName="opacity" dur="0.06s" repeatCount="indefinite" "#,
+    ///     r#"calcMode="discrete" keyTimes="0;0.16666667" values="0;1" /></circle>"#
+    /// )));
+    /// assert!(!svg.get_tags().contains("NaN"));
+    ///
+    /// // A1 can't be played
+    /// let mut wrong = game.clone();
+    /// wrong.moves.push(RecordedMove::new(Move::Play(63)));
+    /// let mut svg = SVGBoard::new();
+    /// let tags = svg.get_tags().to_string();
+    /// let err = svg.draw_game_replay(&wrong, 0.5).unwrap_err();
+    /// assert_eq!(err.to_string(), "illegal move A1 at ply 4");
+    /// assert_eq!(svg.get_tags(), tags);
+    /// ```
+    pub fn draw_game_replay(
+        &mut self,
+        game: &GameRecord,
+        move_duration: f32,
+    ) -> Result<(), RecordError> {
+        let move_duration = if move_duration >= REPLAY_MIN_MOVE_DURATION {
+            move_duration
+        } else {
+            REPLAY_MIN_MOVE_DURATION
+        };
+        let duration = (game.moves.len() as f32 + REPLAY_HOLD_MOVES) * move_duration;
+
+        // key times when the color of each square changes, from the starting position
+        let mut position = game.start;
+        let mut changes: Vec<Vec<(f32, Option<Color>)>> = (0..64)
+            .map(|square| vec![(0f32, square_color(&position, square))])
+            .collect();
+
+        for (ply, rec) in game.moves.iter().enumerate() {
+            position
+                .play(rec.mv)
+                .map_err(|IllegalMove(mv)| RecordError::IllegalMove { ply, mv })?;
+            let key_time = (ply + 1) as f32 * move_duration / duration;
+
+            for (square, square_changes) in changes.iter_mut().enumerate() {
+                let color = square_color(&position, square);
+                if square_changes.last().map(|change| change.1) != Some(color) {
+                    square_changes.push((key_time, color));
+                }
+            }
+        }
+
+        for (square, square_changes) in changes.iter().enumerate() {
+            let center = square_center(square);
+
+            match square_changes.as_slice() {
+                [(_, None)] => (),
+                [(_, Some(color))] => self.svg_tags += &self.disc(center, *color),
+                _ => {
+                    let key_times: Vec<f32> =
+                        square_changes.iter().map(|change| change.0).collect();
+                    let fills: Vec<String> = square_changes
+                        .iter()
+                        .map(|change| match change.1 {
+                            Some(color) => self.disc_color(color).to_string(),
+                            None => String::from("none"),
+                        })
+                        .collect();
+
+                    self.svg_tags += &SVGDoc::animated_circle(
+                        center,
+                        self.style.disc_radius,
+                        &[("fill", &fills[0])],
+                        &SVGDoc::discrete_animation("fill", duration, &key_times, &fills),
+                    );
+
+                    // shading is only visible while the square is occupied
+                    if self.style.disc_shading {
+                        let opacities: Vec<String> = square_changes
+                            .iter()
+                            .map(|change| String::from(if change.1.is_some() { "1" } else { "0" }))
+                            .collect();
+
+                        self.svg_tags += &SVGDoc::animated_circle(
+                            center,
+                            self.style.disc_radius,
+                            &[("fill", "url(#disc-shading)"), ("opacity", &opacities[0])],
+                            &SVGDoc::discrete_animation(
+                                "opacity", duration, &key_times, &opacities,
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mark legal moves, given as a bitboard, with small dots
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::board::position::Position;
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let position = Position::new();
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_position(&position);
+    /// svg.draw_legal_moves(position.legal_moves().bits);
+    /// assert_eq!(svg.get_tags().matches("<circle").count(), 8);
+    /// ```
+    pub fn draw_legal_moves(&mut self, moves: u64) {
+        for square in squares_of(moves) {
+            self.svg_tags +=
+                &SVGDoc::circle(square_center(square), 0.1f32, "fill:black;fill-opacity:0.4");
+        }
+    }
+
+    /// Highlight the last move played with a ring around its disc
+    pub fn draw_last_move(&mut self, square: usize) {
+        self.svg_tags += &SVGDoc::circle(
+            square_center(square),
+            self.style.disc_radius + 0.05f32,
+            "fill:none;stroke:red;stroke-width:0.06",
+        );
+    }
+
+    /// Outline the discs flipped by a move, given as a bitboard
+    pub fn draw_flipped(&mut self, flipped: u64) {
+        for square in squares_of(flipped) {
+            self.svg_tags += &SVGDoc::circle(
+                square_center(square),
+                self.style.disc_radius,
+                "fill:none;stroke:red;stroke-width:0.04;stroke-dasharray:0.1,0.06",
+            );
+        }
+    }
+
+    /// Shade squares, given as a bitboard, with any SVG color and an opacity from 0 to 1. As
+    /// later tags are drawn over earlier ones, shade squares before drawing discs.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.shade_squares(0x8100000000000081, "#ffd700", 0.5);
+    /// assert_eq!(svg.get_tags().matches("fill:#ffd700;fill-opacity:0.5").count(), 4);
+    /// ```
+    pub fn shade_squares(&mut self, squares: u64, color: &str, opacity: f32) {
+        let style = format!("fill:{};fill-opacity:{}", color, opacity);
+
+        for square in squares_of(squares) {
+            let center = square_center(square);
+            self.svg_tags +=
+                &SVGDoc::rect((center.0 - 0.5f32, center.1 - 0.5f32), 1f32, 1f32, &style);
+        }
+    }
+
+    /// Draw an arrow from the center of a square to the one of another square, e.g. to show a
+    /// sequence of moves
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_arrow(26, 20, "blue");
+    /// assert_eq!(svg.get_tags().matches("stroke:blue").count(), 3);
+    /// ```
+    pub fn draw_arrow(&mut self, from: usize, to: usize, color: &str) {
+        let start = square_center(from);
+        let end = square_center(to);
+        let style = format!("stroke:{};stroke-width:0.08;stroke-linecap:round", color);
+
+        let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+        let length = (dx * dx + dy * dy).sqrt();
+        if length == 0f32 {
+            return;
+        }
+        let (ux, uy) = (dx / length, dy / length);
+
+        self.svg_tags += &SVGDoc::line(start, end, &style);
+
+        // arrow head: two wings at +/- 30 degrees from the shaft
+        let (cos, sin) = (0.866f32, 0.5f32);
+        for side in [-1f32, 1f32].iter() {
+            let wing = (
+                end.0 - 0.3f32 * (ux * cos - side * uy * sin),
+                end.1 - 0.3f32 * (uy * cos + side * ux * sin),
+            );
+            self.svg_tags += &SVGDoc::line(end, wing, &style);
+        }
+    }
+
+    /// Write a short label, like an evaluation score, in the middle of a square
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::svg_board::SVGBoard;
+    ///
+    /// let mut svg = SVGBoard::new();
+    /// svg.draw_square_label(26, "+4", "red");
+    /// assert!(svg.get_tags().contains(r#"x="6.5" y="5.62" text-anchor="middle""#));
+    /// assert!(svg.get_tags().contains(">+4</text>"));
+    /// ```
+    pub fn draw_square_label(&mut self, square: usize, label: &str, color: &str) {
+        let center = square_center(square);
+
+        self.svg_tags += &SVGDoc::text_with_anchor(
+            (center.0, center.1 + 0.12f32),
+            "middle",
+            &format!(
+                "font-family:{};font-size:0.35px;fill:{}",
+                self.style.font_family, color
+            ),
+            label,
+        );
+    }
+
     /// Draw black or white piece using a bitboard (u64 int) to illustrate the bit shifting
     pub fn draw_pieces_from_vecint64(&mut self, indexes: &mut VecInt64) {
         println!("{:?}", indexes);
@@ -158,16 +595,9 @@ impl SVGBoard {
     /// Draw black or white piece using a (x,y) coordinates    
     pub fn draw_piece_from_xy(&mut self, xy_coord: (usize, usize), color: Color) {
         // draw circle with valid color
-        self.svg_tags += &SVGDoc::circle(
+        self.svg_tags += &self.disc(
             ((xy_coord.0 as f32) + 1.5f32, (xy_coord.1 as f32) + 1.5f32),
-            0.4f32,
-            &format!(
-                "fill:{}",
-                match color {
-                    Color::Black => "black",
-                    Color::White => "white",
-                }
-            ),
+            color,
         );
     }
 
@@ -180,7 +610,7 @@ impl SVGBoard {
     /// Draw black or white pieces using a vector algebric coordinates
     pub fn draw_pieces_from_algebric(&mut self, algebric_coord_vec: Vec<&str>, color: Color) {
         for coord in &algebric_coord_vec {
-            self.draw_piece_from_algebric(coord, color.clone());
+            self.draw_piece_from_algebric(coord, color);
         }
     }
 }
@@ -2,6 +2,13 @@
 
 pub struct SVGDoc {}
 
+// text content with the XML special characters escaped
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
 impl SVGDoc {
     pub fn new_document(view_box: &str, width: f32, height: f32) -> String {
         format!(r#"<?xml version="1.0" encoding="UTF-8"?><svg xmlns="http://www.w3.org/2000/svg" viewBox="{}" width="{}" height="{}">"#, view_box, width, height)
@@ -24,14 +31,30 @@ impl SVGDoc {
     pub fn text(x: (f32, f32), style: &str, text: &str) -> String {
         format!(
             r#"<text x="{}" y="{}" style="{}">{}</text>"#,
-            x.0, x.1, style, text
+            x.0,
+            x.1,
+            style,
+            escape(text)
         )
     }
 
+    /// Text anchored at its start, middle or end. `&`, `<` and `>` are escaped.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::svg_doc::SVGDoc;
+    ///
+    /// let text = SVGDoc::text_with_anchor((5.0, 9.5), "middle", "fill:black", "Tom & <Jerry>");
+    /// assert!(text.ends_with(">Tom &amp; &lt;Jerry&gt;</text>"));
+    /// ```
     pub fn text_with_anchor(x: (f32, f32), anchor: &str, style: &str, text: &str) -> String {
         format!(
             r#"<text x="{}" y="{}" text-anchor="{}" style="{}">{}</text>"#,
-            x.0, x.1, anchor, style, text
+            x.0,
+            x.1,
+            anchor,
+            style,
+            escape(text)
         )
     }
 
@@ -42,6 +65,73 @@ impl SVGDoc {
         )
     }
 
+    /// Circle whose attributes are animated by SMIL tags like the ones of `discrete_animation`.
+    /// Initial values like the fill are given as attributes, as a style would override the
+    /// animated values.
+    ///
+    /// # Examples
+    /// ```
+    /// use othlib::svg::svg_doc::SVGDoc;
+    ///
+    /// let attributes = [("fill", "black"), ("opacity", "0")];
+    /// let circle = SVGDoc::animated_circle((1.5, 1.5), 0.4, &attributes, "");
+    /// assert_eq!(
+    ///     circle,
+    ///     r#"<circle cx="1.5" cy="1.5" r="0.4" fill="black" opacity="0"></circle>"#
+    /// );
+    /// ```
+    pub fn animated_circle(
+        center: (f32, f32),
+        radius: f32,
+        attributes: &[(&str, &str)],
+        animations: &str,
+    ) -> String {
+        let attributes: String = attributes
+            .iter()
+            .map(|(name, value)| format!(r#" {}="{}""#, name, value))
+            .collect();
+
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}"{}>{}</circle>"#,
+            center.0, center.1, radius, attributes, animations
+        )
+    }
+
+    /// SMIL animation of an attribute, looping forever, which jumps to each value at its key
+    /// time (from 0 to 1, the first one being 0) of a `duration` in seconds
+    pub fn discrete_animation(
+        attribute: &str,
+        duration: f32,
+        key_times: &[f32],
+        values: &[String],
+    ) -> String {
+        let key_times: Vec<String> = key_times.iter().map(|t| t.to_string()).collect();
+
+        format!(
+            r#"<animate attributeName="{}" dur="{}s" repeatCount="indefinite" calcMode="discrete" keyTimes="{}" values="{}" />"#,
+            attribute,
+            duration,
+            key_times.join(";"),
+            values.join(";")
+        )
+    }
+
+    /// Radial gradient lit from the top left, to be used as `fill:url(#id)`. Stops are given as
+    /// (offset from 0 to 1, color, opacity).
+    pub fn radial_gradient(id: &str, stops: &[(f32, &str, f32)]) -> String {
+        let mut s = format!(
+            r#"<defs><radialGradient id="{}" cx="0.35" cy="0.35" r="0.65">"#,
+            id
+        );
+        for (offset, color, opacity) in stops {
+            s += &format!(
+                r#"<stop offset="{}" stop-color="{}" stop-opacity="{}" />"#,
+                offset, color, opacity
+            );
+        }
+        s + "</radialGradient></defs>"
+    }
+
     pub fn end_document() -> String {
         String::from("</svg>")
     }
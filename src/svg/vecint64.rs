@@ -3,6 +3,12 @@ pub struct VecInt64 {
     pub v64: Vec<i8>,
 }
 
+impl Default for VecInt64 {
+    fn default() -> Self {
+        VecInt64::new()
+    }
+}
+
 impl VecInt64 {
     /// New vector is initialized to [63,62,.....,2,1,0]
     ///
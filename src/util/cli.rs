@@ -0,0 +1,27 @@
+// Helpers shared by the command line tools
+
+use crate::game::import::Importer;
+use crate::game::record::GameRecord;
+
+/// Print an error message and exit with code 1
+pub fn exit_with(msg: String) -> ! {
+    eprintln!("{}", msg);
+    ::std::process::exit(1);
+}
+
+/// Games of a WTHOR or CSV file, see `Importer::read_games`. Wrong games are reported and
+/// skipped, and the tool exits if the file can't be read.
+pub fn read_games(importer: &Importer, file_name: &str) -> Vec<GameRecord> {
+    importer
+        .read_games(file_name)
+        .unwrap_or_else(|e| exit_with(e.to_string()))
+        .into_iter()
+        .filter_map(|game| match game {
+            Ok(game) => Some(game),
+            Err(e) => {
+                eprintln!("{}: {}", file_name, e);
+                None
+            }
+        })
+        .collect()
+}
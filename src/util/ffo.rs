@@ -0,0 +1,143 @@
+// FFO endgame test suite runner: solve OBF positions and check their known scores
+
+use std::env;
+use std::fs;
+use std::time::Instant;
+
+extern crate othlib;
+
+use othlib::engine::endgame::EndgameSolver;
+use othlib::game::obf::{ObfPosition, FFO_FIRST, FFO_OBF};
+
+const USAGE: &str = "ffo [--first <id>] [--range <from>-<to>] [--threads <n>] [<obf file>]
+
+Solve each position of an OBF file (e.g. the FFO endgame suite, positions #1 to #79) and check
+the best score and move against the known ones. Without a file, the FFO positions shipped in
+data/ffo.obf are solved. Positions are numbered from <id>, 1 by default for a file, so that a
+file holding FFO #40 to #59 is run with --first 40. Use --range to only solve some of them. The
+solver uses a single thread unless --threads is given. Exits with a non-zero code if a result is
+wrong.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let mut bounds = range.splitn(2, '-');
+    let from = bounds.next()?.trim().parse().ok()?;
+    let to = match bounds.next() {
+        Some(to) => to.trim().parse().ok()?,
+        None => from,
+    };
+    Some((from, to))
+}
+
+fn main() {
+    let mut first = None;
+    let mut range = (0, usize::MAX);
+    let mut threads = 1;
+    let mut path: Option<String> = None;
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--first" => {
+                first = Some(
+                    args.next()
+                        .and_then(|id| id.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--range" => {
+                range = args
+                    .next()
+                    .and_then(|r| parse_range(&r))
+                    .unwrap_or_else(|| usage())
+            }
+            "--threads" => {
+                threads = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            _ => path = Some(arg),
+        }
+    }
+    let (text, first) = match &path {
+        Some(path) => {
+            let text = fs::read_to_string(path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", path, e);
+                ::std::process::exit(1);
+            });
+            (text, first.unwrap_or(1))
+        }
+        None => (FFO_OBF.to_string(), first.unwrap_or(FFO_FIRST)),
+    };
+
+    let positions = ObfPosition::all_from_obf(&text).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.as_deref().unwrap_or("data/ffo.obf"), e);
+        ::std::process::exit(1);
+    });
+
+    let mut solver = EndgameSolver::with_threads(threads);
+    let (mut total_nodes, mut total_time, mut failures) = (0u64, 0f64, 0);
+
+    println!(
+        "{:>4} {:>7} {:>6} {:>5} {:>8} {:>14} {:>9} {:>12}  result",
+        "#", "empties", "score", "move", "expected", "nodes", "time", "nodes/s"
+    );
+
+    for (i, obf) in positions.iter().enumerate() {
+        let id = first + i;
+        if id < range.0 || id > range.1 {
+            continue;
+        }
+
+        solver.clear();
+        let start = Instant::now();
+        let result = solver.solve(&obf.position);
+        let elapsed = start.elapsed().as_secs_f64();
+
+        let expected = match obf.best_score() {
+            Some(score) => format!("{:+}", score),
+            None => "?".to_string(),
+        };
+        let ok = match obf.best_score() {
+            Some(score) => score == result.score && obf.best_moves().contains(&result.best_move),
+            None => true,
+        };
+        if !ok {
+            failures += 1;
+        }
+
+        println!(
+            "{:>4} {:>7} {:>+6} {:>5} {:>8} {:>14} {:>8.3}s {:>12.0}  {}",
+            id,
+            obf.position.nb_empty(),
+            result.score,
+            result.best_move.to_string(),
+            expected,
+            result.nodes,
+            elapsed,
+            result.nodes as f64 / elapsed.max(1e-9),
+            if ok { "ok" } else { "WRONG" }
+        );
+
+        total_nodes += result.nodes;
+        total_time += elapsed;
+    }
+
+    println!(
+        "total: {} nodes in {:.3}s ({:.0} nodes/s), {} wrong",
+        total_nodes,
+        total_time,
+        total_nodes as f64 / total_time.max(1e-9),
+        failures
+    );
+
+    if failures > 0 {
+        ::std::process::exit(2);
+    }
+}
@@ -25,7 +25,7 @@ impl FromVec for u64 {
 
         // indexes are positions where I need '1'
         for i in &indexes {
-            v[63 - *i as usize] = '1';
+            v[63 - *i] = '1';
         }
 
         // export Vec<char> as a string
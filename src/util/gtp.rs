@@ -0,0 +1,118 @@
+// GTP-style text protocol engine, on stdin and stdout or on a local TCP socket
+
+use std::env;
+use std::io::{self, BufReader};
+use std::net::TcpListener;
+use std::time::Duration;
+
+extern crate othlib;
+
+use othlib::engine::eval::{Evaluator, MobilityEvaluator, PatternEvaluator};
+use othlib::engine::limits::SearchLimits;
+use othlib::engine::probcut::ProbCut;
+use othlib::engine::search::Engine;
+use othlib::protocol::gtp::Gtp;
+use othlib::util::cli::exit_with;
+
+const USAGE: &str = "gtp [--tcp <port>] [--depth <n> | --time <ms>] [--weights <weights>]
+      [--probcut <params>] [--threads <n>]
+
+Play through a GTP-style text protocol on stdin and stdout, or on connections to the local TCP
+<port>, one at a time. Moves are searched to the given depth (10 by default) or for a fixed time
+per move. Positions are evaluated with the pattern weights of the --weights file, or with the
+mobility evaluation. With --probcut, the midgame search is selective, using parameters written
+by the probcut utility. Searches and endgame solves use <n> threads (1 by default).";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut port: Option<u16> = None;
+    let mut limits = SearchLimits::depth(10);
+    let mut weights: Option<String> = None;
+    let mut probcut: Option<String> = None;
+    let mut threads = 1;
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tcp" => {
+                port = Some(
+                    args.next()
+                        .and_then(|p| p.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--depth" => {
+                limits = SearchLimits::depth(
+                    args.next()
+                        .and_then(|n| n.parse().ok())
+                        .filter(|&n| n > 0)
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "--time" => {
+                limits = SearchLimits::move_time(Duration::from_millis(
+                    args.next()
+                        .and_then(|ms| ms.parse().ok())
+                        .unwrap_or_else(|| usage()),
+                ))
+            }
+            "--weights" => weights = Some(args.next().unwrap_or_else(|| usage())),
+            "--probcut" => probcut = Some(args.next().unwrap_or_else(|| usage())),
+            "--threads" => {
+                threads = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            _ => usage(),
+        }
+    }
+
+    let evaluator: Box<dyn Evaluator + Send> = match &weights {
+        Some(file_name) => Box::new(
+            PatternEvaluator::load(file_name)
+                .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e))),
+        ),
+        None => Box::new(MobilityEvaluator),
+    };
+
+    let mut engine = Engine::new(evaluator);
+    engine.set_threads(threads);
+    if let Some(file_name) = &probcut {
+        let params =
+            ProbCut::load(file_name).unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e)));
+        engine.set_probcut(Some(params));
+    }
+    let mut session = Gtp::new(engine, limits);
+
+    match port {
+        None => {
+            let stdin = io::stdin();
+            let stdout = io::stdout();
+            session
+                .run(stdin.lock(), &mut stdout.lock())
+                .unwrap_or_else(|e| exit_with(e.to_string()));
+        }
+        Some(port) => {
+            let listener = TcpListener::bind(("127.0.0.1", port))
+                .unwrap_or_else(|e| exit_with(format!("port {}: {}", port, e)));
+
+            // each connection continues the same game, until a clear_board
+            for stream in listener.incoming() {
+                let result = stream.and_then(|mut stream| {
+                    let input = BufReader::new(stream.try_clone()?);
+                    session.run(input, &mut stream)
+                });
+                if let Err(e) = result {
+                    eprintln!("{}", e);
+                }
+            }
+        }
+    }
+}
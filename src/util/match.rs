@@ -0,0 +1,315 @@
+// Play a match between two engine configurations, to tell whether a change made an engine stronger
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::str::FromStr;
+use std::time::Duration;
+
+extern crate othlib;
+
+use othlib::board::notation::{moves_to_string, Case};
+use othlib::board::position::Move;
+use othlib::engine::eval::{Evaluator, MobilityEvaluator, PatternEvaluator};
+use othlib::engine::limits::SearchLimits;
+use othlib::engine::probcut::ProbCut;
+use othlib::engine::search::Engine;
+use othlib::engine::tournament::{play_game, MatchStats, Player, Sprt, SprtStatus};
+use othlib::game::record::GameRecord;
+use othlib::game::wthor::write_games;
+use othlib::util::cli::exit_with;
+
+const USAGE: &str =
+    "match --a <engine> --b <engine> [--openings <file> | --plies <n>] [--games <n>]
+      [--sprt <elo0>,<elo1>[,<alpha>,<beta>]] [-o <games>]
+
+Play each opening twice, engines swapping colors, and print the wins, draws and losses of engine
+A, its mean disc differential and its Elo difference with B, with a 95% confidence interval.
+Engines are described by comma separated options:
+
+    name=<name>          name in saved games (A or B by default)
+    depth=<n>            search depth (6 by default)
+    time=<ms>            search time per move, instead of a depth
+    weights=<file>       pattern weights, instead of the mobility evaluation
+    probcut=<file>       Multi-ProbCut parameters written by the probcut utility
+    threads=<n>          search threads (1 by default)
+
+Openings are read from a file, one move list per line (e.g. f5d6c3), or are all the lines of
+--plies moves starting with F5 (4 by default). The match stops after --games games, or when the
+SPRT of H0: 'the Elo difference is elo0' against H1: 'it is elo1' accepts one of them (error rates
+alpha and beta are 0.05 by default). Games are saved to the -o file, as WTHOR if its extension is
+.wtb and as GGF otherwise.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+// value of a numeric engine option
+fn positive<T: FromStr + PartialOrd + Default>(key: &str, value: &str) -> T {
+    value
+        .parse()
+        .ok()
+        .filter(|n| *n > T::default())
+        .unwrap_or_else(|| exit_with(format!("invalid value for {}: '{}'", key, value)))
+}
+
+// build a player from its description
+fn player(spec: &str, default_name: &str) -> Player {
+    let mut name = default_name.to_string();
+    let mut limits = SearchLimits::depth(6);
+    let mut evaluator: Box<dyn Evaluator + Send> = Box::new(MobilityEvaluator);
+    let mut probcut = None;
+    let mut threads = 1;
+
+    for option in spec.split(',').filter(|o| !o.is_empty()) {
+        let (key, value) = option
+            .split_once('=')
+            .unwrap_or_else(|| exit_with(format!("invalid engine option '{}'", option)));
+
+        match key {
+            "name" => name = value.to_string(),
+            "depth" => limits = SearchLimits::depth(positive(key, value)),
+            "time" => limits = SearchLimits::move_time(Duration::from_millis(positive(key, value))),
+            "weights" => {
+                evaluator = Box::new(
+                    PatternEvaluator::load(value)
+                        .unwrap_or_else(|e| exit_with(format!("{}: {}", value, e))),
+                )
+            }
+            "probcut" => {
+                probcut = Some(
+                    ProbCut::load(value).unwrap_or_else(|e| exit_with(format!("{}: {}", value, e))),
+                )
+            }
+            "threads" => threads = positive(key, value),
+            _ => exit_with(format!("unknown engine option '{}'", key)),
+        }
+    }
+
+    let mut engine = Engine::new(evaluator);
+    engine.set_threads(threads);
+    engine.set_probcut(probcut);
+    Player::new(&name, engine, limits)
+}
+
+// all the lines of `plies` moves from the standard position starting with F5, the other first
+// moves being symmetric
+fn all_openings(plies: usize) -> Vec<GameRecord> {
+    let f5: Move = "F5".parse().unwrap();
+    let mut lines = vec![GameRecord::from_moves(&[f5]).unwrap()];
+
+    for _ in 1..plies {
+        let mut next = Vec::new();
+        for game in lines {
+            let position = game.final_position();
+            if position.is_game_over() {
+                next.push(game);
+                continue;
+            }
+
+            let mut position = position;
+            if position.must_pass() {
+                // the pass is inserted by from_moves
+                position.play(Move::Pass).unwrap();
+            }
+            let mut moves = position.legal_moves().bits;
+            while moves != 0 {
+                let square = moves.trailing_zeros() as usize;
+                let mut line: Vec<Move> = game.moves.iter().map(|rec| rec.mv).collect();
+                line.push(Move::Play(square));
+                next.push(GameRecord::from_moves(&line).unwrap());
+                moves &= moves - 1;
+            }
+        }
+        lines = next;
+    }
+
+    lines
+}
+
+fn read_openings(file_name: &str) -> Vec<GameRecord> {
+    let text = fs::read_to_string(file_name)
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e)));
+
+    text.lines()
+        .map(str::trim)
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(i, line)| {
+            GameRecord::from_move_string(line)
+                .unwrap_or_else(|e| exit_with(format!("{}:{}: {}", file_name, i + 1, e)))
+        })
+        .collect()
+}
+
+fn parse_sprt(text: &str) -> Option<Sprt> {
+    let values: Vec<f64> = text
+        .split(',')
+        .map(|v| v.parse().ok())
+        .collect::<Option<_>>()?;
+
+    let mut sprt = match values.as_slice() {
+        [elo0, elo1] | [elo0, elo1, _, _] if elo0 < elo1 => Sprt::new(*elo0, *elo1),
+        _ => return None,
+    };
+    if let [_, _, alpha, beta] = values.as_slice() {
+        sprt.alpha = *alpha;
+        sprt.beta = *beta;
+    }
+
+    Some(sprt).filter(|s| (0.0..1.0).contains(&s.alpha) && (0.0..1.0).contains(&s.beta))
+}
+
+fn save_games(file_name: &str, games: &[GameRecord]) {
+    let file =
+        File::create(file_name).unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e)));
+    let mut w = BufWriter::new(file);
+
+    let result = if file_name.to_lowercase().ends_with(".wtb") {
+        let wthor: Vec<_> = games
+            .iter()
+            .map(|game| game.to_wthor().unwrap_or_else(|e| exit_with(e.to_string())))
+            .collect();
+        write_games(&mut w, &wthor, 0)
+    } else {
+        games
+            .iter()
+            .try_for_each(|game| writeln!(w, "{}", game.to_ggf()))
+    };
+
+    result
+        .and_then(|_| w.flush())
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e)));
+}
+
+fn print_stats(stats: &MatchStats, a: &str, b: &str) {
+    let (elo, margin) = stats.elo();
+    println!(
+        "{} vs {}: {} games, +{} ={} -{}, score {:.1}%, discs {:+.2} (sd {:.2}), Elo {:+.1} +/- {:.1}",
+        a,
+        b,
+        stats.games(),
+        stats.wins,
+        stats.draws,
+        stats.losses,
+        100.0 * stats.score(),
+        stats.mean_discs(),
+        stats.discs_deviation(),
+        elo,
+        margin
+    );
+}
+
+fn main() {
+    let mut spec_a: Option<String> = None;
+    let mut spec_b: Option<String> = None;
+    let mut openings_file: Option<String> = None;
+    let mut plies = 4;
+    let mut max_games = u32::MAX;
+    let mut sprt: Option<Sprt> = None;
+    let mut output: Option<String> = None;
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--a" => spec_a = Some(args.next().unwrap_or_else(|| usage())),
+            "--b" => spec_b = Some(args.next().unwrap_or_else(|| usage())),
+            "--openings" => openings_file = Some(args.next().unwrap_or_else(|| usage())),
+            "--plies" => {
+                plies = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            "--games" => {
+                max_games = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            "--sprt" => {
+                sprt = Some(
+                    args.next()
+                        .and_then(|s| parse_sprt(&s))
+                        .unwrap_or_else(|| usage()),
+                )
+            }
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => usage(),
+        }
+    }
+
+    let mut a = player(&spec_a.unwrap_or_else(|| usage()), "A");
+    let mut b = player(&spec_b.unwrap_or_else(|| usage()), "B");
+    let openings = match &openings_file {
+        Some(file_name) => read_openings(file_name),
+        None => all_openings(plies),
+    };
+    if openings.is_empty() {
+        exit_with("no opening to play".to_string());
+    }
+
+    let mut stats = MatchStats::new();
+    let mut games = Vec::new();
+    let mut status = SprtStatus::Continue;
+
+    'openings: for opening in &openings {
+        for a_is_black in [true, false] {
+            if stats.games() >= max_games || status != SprtStatus::Continue {
+                break 'openings;
+            }
+
+            let game = if a_is_black {
+                play_game(&mut a, &mut b, opening)
+            } else {
+                play_game(&mut b, &mut a, opening)
+            }
+            .unwrap_or_else(|e| exit_with(format!("game {}: {}", stats.games() + 1, e)));
+            let black_discs = game.final_position().final_score();
+            stats.add(if a_is_black {
+                black_discs
+            } else {
+                -black_discs
+            });
+
+            let moves: Vec<Move> = opening.moves.iter().map(|rec| rec.mv).collect();
+            eprintln!(
+                "game {}: {}, {} - {}: {:+}",
+                stats.games(),
+                moves_to_string(&moves, "", Case::Lower),
+                game.black_player,
+                game.white_player,
+                black_discs
+            );
+            games.push(game);
+
+            if let Some(sprt) = &sprt {
+                status = sprt.status(&stats);
+            }
+        }
+    }
+
+    print_stats(&stats, &a.name, &b.name);
+    if let Some(sprt) = &sprt {
+        let (lower, upper) = sprt.bounds();
+        println!(
+            "SPRT elo0={} elo1={} alpha={} beta={}: LLR {:.2} in [{:.2}, {:.2}], {}",
+            sprt.elo0,
+            sprt.elo1,
+            sprt.alpha,
+            sprt.beta,
+            sprt.llr(&stats),
+            lower,
+            upper,
+            status
+        );
+    }
+
+    if let Some(file_name) = &output {
+        save_games(file_name, &games);
+    }
+}
@@ -0,0 +1,69 @@
+// NBoard protocol engine, to be loaded in the NBoard GUI or in a tournament manager
+
+use std::env;
+use std::io;
+
+extern crate othlib;
+
+use othlib::engine::eval::{Evaluator, MobilityEvaluator, PatternEvaluator};
+use othlib::engine::probcut::ProbCut;
+use othlib::engine::search::Engine;
+use othlib::protocol::nboard::NBoard;
+use othlib::util::cli::exit_with;
+
+const USAGE: &str = "nboard [--weights <weights>] [--probcut <params>] [--threads <n>]
+
+Play through the NBoard protocol on stdin and stdout. Positions are evaluated with the pattern
+weights of the --weights file, or with the mobility evaluation. With --probcut, the midgame search
+is selective, using parameters written by the probcut utility. Searches and endgame solves use
+<n> threads (1 by default).";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut weights: Option<String> = None;
+    let mut probcut: Option<String> = None;
+    let mut threads = 1;
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--weights" => weights = Some(args.next().unwrap_or_else(|| usage())),
+            "--probcut" => probcut = Some(args.next().unwrap_or_else(|| usage())),
+            "--threads" => {
+                threads = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            _ => usage(),
+        }
+    }
+
+    let evaluator: Box<dyn Evaluator + Send> = match &weights {
+        Some(file_name) => Box::new(
+            PatternEvaluator::load(file_name)
+                .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e))),
+        ),
+        None => Box::new(MobilityEvaluator),
+    };
+
+    let mut engine = Engine::new(evaluator);
+    engine.set_threads(threads);
+    if let Some(file_name) = &probcut {
+        let params =
+            ProbCut::load(file_name).unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e)));
+        engine.set_probcut(Some(params));
+    }
+
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    NBoard::new(engine)
+        .run(stdin.lock(), &mut stdout.lock())
+        .unwrap_or_else(|e| exit_with(e.to_string()));
+}
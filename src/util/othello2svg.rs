@@ -4,12 +4,9 @@ use std::env;
 
 extern crate othlib;
 
-use othlib::board::bitboard::BitBoard;
 use othlib::board::color::Color;
-use othlib::board::direction::Direction;
 use othlib::svg::svg_board::SVGBoard;
 use othlib::svg::vecint64::VecInt64;
-use othlib::util::from_vec::FromVec;
 
 // single argument: output file location
 fn main() {
@@ -21,10 +18,10 @@ fn main() {
     }
 
     // file name is the 2nd argument
-    let svg_file = &args[1];
+    let _svg_file = &args[1];
 
     // //let mut bb = BitBoard::new();
-    // //bb.set_value(u64::max_value(), Color::White);
+    // //bb.set_value(u64::MAX, Color::White);
     // //let dir = bb.shift(Direction::Up_Right, Color::White);
 
     // //svg.draw_pieces_from_algebric(vec!["D4", "D5", "D6", "E5"], Color::White);
@@ -45,7 +42,7 @@ fn main() {
 
     // // draw pieces depending on set bits in the u64 value
     // let mask = 0b11111110_11111110_11111110_11111110_11111110_11111110_11111110_11111110;
-    // let value = (u64::max_value() << 1) & mask;
+    // let value = (u64::MAX << 1) & mask;
     // svg.draw_pieces_from_u64(value, Color::White);
 
     // // trick to keep track of the piece number
@@ -64,14 +61,14 @@ fn main() {
     generate_svg(
         8,
         'R',
-        u64::max_value(),
+        u64::MAX,
         "Moving South",
         "doc/images/full_board_south.svg",
     );
     generate_svg(
         8,
         'L',
-        u64::max_value(),
+        u64::MAX,
         "Moving North",
         "doc/images/full_board_north.svg",
     );
@@ -125,8 +122,8 @@ pub fn generate_svg(shift: usize, dir: char, mask: u64, legend: &str, filename:
 
     // compute value and vecint64
     let value = match dir {
-        'L' => (u64::max_value() << shift) & mask,
-        'R' => (u64::max_value() >> shift) & mask,
+        'L' => (u64::MAX << shift) & mask,
+        'R' => (u64::MAX >> shift) & mask,
         _ => unimplemented!("Not implemented!"),
     };
 
@@ -0,0 +1,92 @@
+// Perft utility: count the move paths from a position to check move generation
+
+use std::env;
+use std::time::Instant;
+
+extern crate othlib;
+
+use othlib::board::movegen;
+use othlib::board::perft::{check_generators, divide, perft_with};
+use othlib::game::record::GameRecord;
+
+const USAGE: &str = "perft [--divide] [--check] [--backend <name>] [--moves <moves>] <depth>
+
+Count the leaf nodes from the starting position, or from the position reached after <moves>
+(e.g. f5d6c3). With --divide, counts are given for each move. With --check, all move generators
+are compared on every position of the tree. Backends are: linecap, shift, koggestone and avx2
+when supported. The fastest one is used by default.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut depth: Option<usize> = None;
+    let mut with_divide = false;
+    let mut with_check = false;
+    let mut moves = String::new();
+    let mut generator = movegen::best();
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--divide" => with_divide = true,
+            "--check" => with_check = true,
+            "--moves" => moves = args.next().unwrap_or_else(|| usage()),
+            "--backend" => {
+                let name = args.next().unwrap_or_else(|| usage());
+                generator = movegen::by_name(&name).unwrap_or_else(|| usage());
+            }
+            _ => depth = Some(arg.parse().unwrap_or_else(|_| usage())),
+        }
+    }
+    let depth = depth.unwrap_or_else(|| usage());
+
+    // starting position
+    let position = match GameRecord::from_move_string(&moves) {
+        Ok(game) => game.final_position(),
+        Err(e) => {
+            eprintln!("{}", e);
+            ::std::process::exit(1);
+        }
+    };
+
+    if with_check {
+        if let Err((name, wrong)) = check_generators(&position, depth) {
+            eprintln!(
+                "{} move generator disagrees on position {} ({:?} to move)",
+                name,
+                wrong.to_squares('X', 'O', '-'),
+                wrong.side
+            );
+            ::std::process::exit(2);
+        }
+        println!("move generators agree up to depth {}", depth);
+    }
+
+    let start = Instant::now();
+
+    let count = if with_divide {
+        let (counts, total) = divide(generator.as_ref(), &position, depth);
+        for (mv, count) in counts {
+            println!("{}: {}", mv, count.nodes);
+        }
+        total
+    } else {
+        perft_with(generator.as_ref(), &position, depth)
+    };
+
+    let elapsed = start.elapsed().as_secs_f64();
+    println!(
+        "{} depth {}: {} nodes, {} passes, {} game ends in {:.3}s ({:.0} nodes/s)",
+        generator.name(),
+        depth,
+        count.nodes,
+        count.passes,
+        count.game_ends,
+        elapsed,
+        count.nodes as f64 / elapsed.max(1e-9)
+    );
+}
@@ -0,0 +1,132 @@
+// Calibrate the Multi-ProbCut parameters on positions of WTHOR games or wthor2csv files
+
+use std::env;
+use std::time::Instant;
+
+extern crate othlib;
+
+use othlib::engine::eval::{Evaluator, MobilityEvaluator, PatternEvaluator, STAGES};
+use othlib::engine::probcut::{Calibrator, MIN_DEPTH};
+use othlib::engine::search::Engine;
+use othlib::game::import::{parse_delimiter, Importer};
+use othlib::util::cli::{exit_with, read_games};
+
+const USAGE: &str = "probcut [--weights <weights>] [--depth <n>] [--every <n>] [--positions <n>]
+      [-d <delimiter>] -o <params> <games>...
+
+Search sample positions of the games at all depths up to --depth (8 by default), fit the
+regression of deep scores on shallow ones for each stage and depth, and write the Multi-ProbCut
+parameters to the <params> file. One position every --every plies (5 by default) is sampled, up
+to --positions positions. Scores come from the pattern evaluation of the --weights file, or from
+the mobility evaluation. Game files are either WTHOR game files (.wtb) or CSV files written by
+wthor2csv, with the given delimiter (';' by default).";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut depth = 8;
+    let mut every = 5;
+    let mut max_positions = usize::MAX;
+    let mut importer = Importer::default();
+    let mut weights: Option<String> = None;
+    let mut output: Option<String> = None;
+    let mut files = Vec::new();
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--weights" => weights = Some(args.next().unwrap_or_else(|| usage())),
+            "--depth" => {
+                depth = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n >= MIN_DEPTH)
+                    .unwrap_or_else(|| usage())
+            }
+            "--every" => {
+                every = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .filter(|&n| n > 0)
+                    .unwrap_or_else(|| usage())
+            }
+            "--positions" => {
+                max_positions = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "-d" => {
+                importer.delimiter = args
+                    .next()
+                    .and_then(|d| parse_delimiter(&d))
+                    .unwrap_or_else(|| usage())
+            }
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => files.push(arg),
+        }
+    }
+    let output = output.unwrap_or_else(|| usage());
+    if files.is_empty() {
+        usage();
+    }
+
+    let evaluator: Box<dyn Evaluator + Send> = match &weights {
+        Some(file_name) => Box::new(
+            PatternEvaluator::load(file_name)
+                .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e))),
+        ),
+        None => Box::new(MobilityEvaluator),
+    };
+    let mut engine = Engine::new(evaluator);
+    let mut calibrator = Calibrator::new(depth);
+
+    // search sample positions
+    let start = Instant::now();
+    let mut positions = 0;
+    'files: for file_name in &files {
+        for game in read_games(&importer, file_name) {
+            for ply in (0..=game.moves.len()).step_by(every) {
+                if positions >= max_positions {
+                    break 'files;
+                }
+                if calibrator.add_position(&mut engine, &game.position_at(ply)) {
+                    positions += 1;
+                    if positions % 100 == 0 {
+                        eprintln!(
+                            "{} positions searched in {:.1}s",
+                            positions,
+                            start.elapsed().as_secs_f64()
+                        );
+                    }
+                }
+            }
+        }
+    }
+    println!(
+        "{} positions searched in {:.1}s",
+        positions,
+        start.elapsed().as_secs_f64()
+    );
+
+    let probcut = calibrator.fit();
+    println!("stage depth shallow      a      b  sigma");
+    for stage in 0..STAGES {
+        for d in MIN_DEPTH..=depth {
+            if let Some(r) = probcut.regression(stage, d) {
+                println!(
+                    "{:>5} {:>5} {:>7} {:>6.3} {:>6.2} {:>6.2}",
+                    stage, d, r.shallow, r.a, r.b, r.sigma
+                );
+            }
+        }
+    }
+
+    probcut
+        .save(&output)
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", output, e)));
+}
@@ -0,0 +1,111 @@
+// Train the pattern evaluation weights on WTHOR games or wthor2csv files
+
+use std::env;
+use std::time::Instant;
+
+extern crate othlib;
+
+use othlib::engine::eval::PatternEvaluator;
+use othlib::engine::train::{Label, Trainer};
+use othlib::game::import::{parse_delimiter, Importer};
+use othlib::util::cli::{exit_with, read_games};
+
+const USAGE: &str =
+    "train [--label result|theoretical] [--epochs <n>] [--rate <r>] [--init <weights>]
+      [-d <delimiter>] -o <weights> <games>...
+
+Fit the pattern weights on all positions of the games, and write them to the <weights> file.
+Game files are either WTHOR game files (.wtb) or CSV files written by wthor2csv, with the
+given delimiter (';' by default). Positions are labelled with the final result of the game, or
+its theoretical score. Training starts from zero weights, or from the --init weight file.";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
+
+fn main() {
+    let mut trainer = Trainer::new();
+    let mut label = Label::Result;
+    let mut importer = Importer::default();
+    let mut init: Option<String> = None;
+    let mut output: Option<String> = None;
+    let mut files = Vec::new();
+
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--label" => {
+                label = match args.next().as_deref() {
+                    Some("result") => Label::Result,
+                    Some("theoretical") => Label::Theoretical,
+                    _ => usage(),
+                }
+            }
+            "--epochs" => {
+                trainer.epochs = args
+                    .next()
+                    .and_then(|n| n.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--rate" => {
+                trainer.rate = args
+                    .next()
+                    .and_then(|r| r.parse().ok())
+                    .unwrap_or_else(|| usage())
+            }
+            "--init" => init = Some(args.next().unwrap_or_else(|| usage())),
+            "-d" => {
+                importer.delimiter = args
+                    .next()
+                    .and_then(|d| parse_delimiter(&d))
+                    .unwrap_or_else(|| usage())
+            }
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            _ => files.push(arg),
+        }
+    }
+    let output = output.unwrap_or_else(|| usage());
+    if files.is_empty() {
+        usage();
+    }
+
+    // collect positions
+    for file_name in &files {
+        let games = read_games(&importer, file_name);
+        let positions: usize = games.iter().map(|g| trainer.add_game(g, label)).sum();
+        println!(
+            "{}: {} games, {} positions",
+            file_name,
+            games.len(),
+            positions
+        );
+    }
+
+    let mut evaluator = match &init {
+        Some(file_name) => PatternEvaluator::load(file_name)
+            .unwrap_or_else(|e| exit_with(format!("{}: {}", file_name, e))),
+        None => PatternEvaluator::zero(),
+    };
+
+    let start = Instant::now();
+    let errors = trainer.train(&mut evaluator);
+    println!(
+        "{} positions trained in {:.1}s",
+        trainer.len(),
+        start.elapsed().as_secs_f64()
+    );
+    for (stage, error) in errors.iter().enumerate() {
+        println!(
+            "stage {:>2}: {:>8} positions, RMS error {:.2}",
+            stage,
+            trainer.stage_len(stage),
+            error
+        );
+    }
+
+    evaluator
+        .save(&output)
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", output, e)));
+}
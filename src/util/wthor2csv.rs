@@ -1,56 +1,121 @@
-// Simple utility to convert Othello WTHOR files to plain CSV
+// Simple utility to convert Othello WTHOR files to CSV or JSON Lines
 
 use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 
 extern crate othlib;
 
+use othlib::game::export::{Column, Exporter, Format, DEFAULT_COLUMNS};
+use othlib::game::import::parse_delimiter;
+use othlib::game::record::GameRecord;
 use othlib::game::wthor::{WThorFile, WThorGame, WThorPlayer, WThorTournament};
+use othlib::util::cli::exit_with;
 
-// used to convert to algebric
+const USAGE: &str = "wthor2csv [-d <delimiter>] [-c <col1,col2,...>] [-o <output>] [--json] [--no-header] <players> <tournaments> <games>
+
+columns: year, tournament, black, white, black_score, theoretical_score, moves, final_position, opening";
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    ::std::process::exit(1);
+}
 
-// single argument: output file location
 fn main() {
-    let args: Vec<String> = env::args().collect();
+    let mut delimiter = ';';
+    let mut columns = DEFAULT_COLUMNS.to_vec();
+    let mut output: Option<String> = None;
+    let mut json = false;
+    let mut header = true;
+    let mut files = Vec::new();
 
-    if args.len() != 4 {
-        println!("wthor2csv <players> <tournaments> <games>");
-        ::std::process::exit(1);
+    // manage options
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-d" => {
+                delimiter = args
+                    .next()
+                    .and_then(|d| parse_delimiter(&d))
+                    .unwrap_or_else(|| usage())
+            }
+            "-c" => {
+                let list = args.next().unwrap_or_else(|| usage());
+                columns = list
+                    .split(',')
+                    .map(|c| {
+                        c.parse::<Column>().unwrap_or_else(|e| {
+                            eprintln!("{}", e);
+                            usage()
+                        })
+                    })
+                    .collect();
+            }
+            "-o" => output = Some(args.next().unwrap_or_else(|| usage())),
+            "--json" => json = true,
+            "--no-header" => header = false,
+            _ if arg.starts_with('-') => usage(),
+            _ => files.push(arg),
+        }
+    }
+
+    if files.len() != 3 {
+        usage();
     }
 
     // load WTHOR files
-    let players = WThorFile::<WThorPlayer>::new(&args[1]);
-    let tournaments = WThorFile::<WThorTournament>::new(&args[2]);
-    let games = WThorFile::<WThorGame>::new(&args[3]);
+    let players = WThorFile::<WThorPlayer>::new(&files[0])
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", files[0], e)));
+    let tournaments = WThorFile::<WThorTournament>::new(&files[1])
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", files[1], e)));
+    let games = WThorFile::<WThorGame>::new(&files[2])
+        .unwrap_or_else(|e| exit_with(format!("{}: {}", files[2], e)));
 
-    // now read game file and print out data as CSV
-    for game in &games.data {
-        // convert moves
-        let move_alg: Vec<_> = game.moves.iter().map(|x| convert_to_algebric(x)).collect();
+    let format = if json {
+        Format::JsonLines
+    } else {
+        Format::Csv { delimiter, header }
+    };
+    let exporter = Exporter::new(columns, format);
 
-        // get players & tournaments labels
-        let tournament = &tournaments.data[game.tournament_title_id as usize].tournament;
-        let black_player = &players.data[usize::from(game.black_player_id)].player;
-        let white_player = &players.data[usize::from(game.white_player_id)].player;
-
-        println!(
-            "{};{};{};{};{};{}",
-            tournament,
-            black_player,
-            white_player,
-            game.black_pieces_id,
-            game.theoretical_score,
-            move_alg.join("-")
-        );
-    }
-}
+    // either write to a file or to stdout
+    let mut writer: Box<dyn Write> = match &output {
+        Some(file_name) => Box::new(BufWriter::new(
+            File::create(file_name).expect("Unable to create output file"),
+        )),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
 
-// simple function to convert from WTHOR move to algebric move
-fn convert_to_algebric(r#move: &u8) -> String {
-    const ASCII_UPPER: [char; 8] = ['A', 'B', 'C', 'D', 'E', 'F', 'G', 'H'];
+    exporter
+        .write_header(&mut writer)
+        .expect("Unable to write header");
 
-    // tenth is index on letters
-    let letter = ASCII_UPPER[(r#move % 10) as usize - 1];
-    let digit = format!("{}", r#move / 10);
+    // now read game file and write out data
+    for (i, game) in games.data.iter().enumerate() {
+        let mut record = match GameRecord::from_wthor(game) {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("game #{}: {}", i + 1, e);
+                continue;
+            }
+        };
 
-    format!("{}{}", letter, digit)
+        // get players & tournaments labels
+        let name = |id: u16, names: &Vec<WThorPlayer>| {
+            names
+                .get(usize::from(id))
+                .map_or(String::new(), |p| p.player.clone())
+        };
+        record.black_player = name(game.black_player_id, &players.data);
+        record.white_player = name(game.white_player_id, &players.data);
+        record.tournament = tournaments
+            .data
+            .get(usize::from(game.tournament_title_id))
+            .map(|t| t.tournament.clone());
+        record.date = Some(games.header.game_year.to_string());
+
+        exporter
+            .write_game(&mut writer, &record)
+            .expect("Unable to write game");
+    }
 }
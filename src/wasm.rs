@@ -0,0 +1,233 @@
+// WebAssembly bindings, built with the `wasm` feature:
+//
+//     wasm-pack build --target web -- --features wasm
+//
+// JavaScript gets a `Game` (a position reached from the standard one, with play and undo), search
+// `Limits`, and the `SearchOutput` of a search. Moves are strings in algebric notation, `pass`
+// being a pass, and boards are 64 characters from A1 to H8: `X` for black, `O` for white and `-`
+// for empty squares. The demo page is in `www/`.
+
+use wasm_bindgen::prelude::*;
+
+use crate::board::color::Color;
+use crate::board::notation::{moves_to_string, Case};
+use crate::board::position::{Move, Position};
+use crate::engine::eval::MobilityEvaluator;
+use crate::engine::limits::SearchLimits;
+use crate::engine::search::{Engine, SearchResult};
+use crate::game::record::{GameRecord, RecordedMove};
+use crate::svg::svg_board::SVGBoard;
+
+fn color_name(color: Color) -> String {
+    match color {
+        Color::Black => "black".to_string(),
+        Color::White => "white".to_string(),
+    }
+}
+
+/// When a search must stop
+#[wasm_bindgen]
+pub struct Limits {
+    limits: SearchLimits,
+}
+
+#[wasm_bindgen]
+impl Limits {
+    /// Search to a fixed depth
+    pub fn depth(depth: u32) -> Limits {
+        Limits {
+            limits: SearchLimits::depth(depth),
+        }
+    }
+
+    /// Search for a time in milliseconds
+    pub fn time(ms: u32) -> Limits {
+        Limits {
+            limits: SearchLimits::move_time(std::time::Duration::from_millis(u64::from(ms))),
+        }
+    }
+
+    /// Search a number of positions
+    pub fn nodes(nodes: f64) -> Limits {
+        Limits {
+            limits: SearchLimits::nodes(nodes as u64),
+        }
+    }
+}
+
+/// Outcome of a search
+#[wasm_bindgen]
+pub struct SearchOutput {
+    result: SearchResult,
+}
+
+#[wasm_bindgen]
+impl SearchOutput {
+    #[wasm_bindgen(getter, js_name = bestMove)]
+    pub fn best_move(&self) -> String {
+        self.result.best_move.to_string()
+    }
+
+    /// Disc differential for the color to move
+    #[wasm_bindgen(getter)]
+    pub fn score(&self) -> f32 {
+        self.result.score
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn exact(&self) -> bool {
+        self.result.exact
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn depth(&self) -> u32 {
+        self.result.depth
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn nodes(&self) -> f64 {
+        self.result.nodes as f64
+    }
+
+    #[wasm_bindgen(getter, js_name = timeMs)]
+    pub fn time_ms(&self) -> f64 {
+        self.result.time.as_secs_f64() * 1000.0
+    }
+
+    /// Principal variation, e.g. `F5 D6 C3`
+    #[wasm_bindgen(getter)]
+    pub fn pv(&self) -> String {
+        moves_to_string(&self.result.pv, " ", Case::Upper)
+    }
+}
+
+/// A game from the standard position, with an engine to search its positions
+#[wasm_bindgen]
+pub struct Game {
+    record: GameRecord,
+    engine: Engine,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Game::new()
+    }
+}
+
+#[wasm_bindgen]
+impl Game {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Game {
+        Game {
+            record: GameRecord::default(),
+            engine: Engine::new(Box::new(MobilityEvaluator)),
+        }
+    }
+
+    /// A game after a list of moves, e.g. `f5d6c3`
+    #[wasm_bindgen(js_name = fromMoves)]
+    pub fn from_moves(moves: &str) -> Result<Game, JsError> {
+        let mut game = Game::new();
+        game.record = GameRecord::from_move_string(moves)?;
+        Ok(game)
+    }
+
+    fn position(&self) -> Position {
+        self.record.final_position()
+    }
+
+    /// The 64 squares from A1 to H8
+    pub fn board(&self) -> String {
+        self.position().to_squares('X', 'O', '-')
+    }
+
+    /// `black` or `white`
+    #[wasm_bindgen(js_name = sideToMove)]
+    pub fn side_to_move(&self) -> String {
+        color_name(self.position().side)
+    }
+
+    #[wasm_bindgen(js_name = blackCount)]
+    pub fn black_count(&self) -> u32 {
+        self.position().count(Color::Black)
+    }
+
+    #[wasm_bindgen(js_name = whiteCount)]
+    pub fn white_count(&self) -> u32 {
+        self.position().count(Color::White)
+    }
+
+    /// Legal moves of the color to move, by decreasing bit index
+    #[wasm_bindgen(js_name = legalMoves)]
+    pub fn legal_moves(&self) -> Vec<String> {
+        let mut moves = self.legal_mask();
+        let mut list = Vec::new();
+        while moves != 0 {
+            let square = 63 - moves.leading_zeros() as usize;
+            list.push(Move::Play(square).to_string());
+            moves &= !(1u64 << square);
+        }
+        list
+    }
+
+    /// Legal moves as a bitboard, bit 63 being A1
+    #[wasm_bindgen(js_name = legalMask)]
+    pub fn legal_mask(&self) -> u64 {
+        self.position().legal_moves().bits
+    }
+
+    /// Play a move for the color to move. A color with no legal move passes automatically.
+    pub fn play(&mut self, mv: &str) -> Result<(), JsError> {
+        let mv: Move = mv.parse()?;
+        self.record.push_move(RecordedMove::new(mv))?;
+        Ok(())
+    }
+
+    /// Take back the last move, and the pass before it. Returns false at the start of the game.
+    pub fn undo(&mut self) -> bool {
+        if self.record.moves.pop().is_none() {
+            return false;
+        }
+        if self
+            .record
+            .moves
+            .last()
+            .is_some_and(|rec| rec.mv == Move::Pass)
+        {
+            self.record.moves.pop();
+        }
+        true
+    }
+
+    /// Moves played so far, e.g. `f5d6c3`
+    pub fn moves(&self) -> String {
+        let moves: Vec<Move> = self.record.moves.iter().map(|rec| rec.mv).collect();
+        moves_to_string(&moves, "", Case::Lower)
+    }
+
+    #[wasm_bindgen(js_name = isGameOver)]
+    pub fn is_game_over(&self) -> bool {
+        self.position().is_game_over()
+    }
+
+    /// Final disc differential for black
+    pub fn score(&self) -> i32 {
+        self.position().final_score()
+    }
+
+    /// Search the current position
+    pub fn search(&mut self, limits: &Limits) -> SearchOutput {
+        let position = self.position();
+        SearchOutput {
+            result: self.engine.search(&position, &limits.limits, false),
+        }
+    }
+
+    /// The board as an SVG document
+    pub fn svg(&self) -> String {
+        let mut svg = SVGBoard::new();
+        svg.draw_position(&self.position());
+        svg.close();
+        svg.get_tags().to_string()
+    }
+}
@@ -0,0 +1,54 @@
+// Tests of the WebAssembly bindings, run with:
+//
+//     wasm-pack test --node --features wasm
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use wasm_bindgen_test::*;
+
+use othlib::wasm::{Game, Limits};
+
+#[wasm_bindgen_test]
+fn play_and_undo() {
+    let mut game = Game::new();
+    assert_eq!(game.side_to_move(), "black");
+    assert_eq!(game.legal_moves(), vec!["D3", "C4", "F5", "E6"]);
+
+    game.play("f5").unwrap();
+    assert_eq!(game.side_to_move(), "white");
+    assert_eq!((game.black_count(), game.white_count()), (4, 1));
+    assert!(game.play("a1").is_err());
+
+    assert!(game.undo());
+    assert!(!game.undo());
+    assert_eq!(game.board(), Game::new().board());
+}
+
+#[wasm_bindgen_test]
+fn from_moves() {
+    let game = Game::from_moves("f5d6c3d3c4").unwrap();
+    assert_eq!(game.moves(), "f5d6c3d3c4");
+    assert_eq!(
+        game.legal_mask().count_ones() as usize,
+        game.legal_moves().len()
+    );
+    assert!(Game::from_moves("f5f5").is_err());
+}
+
+#[wasm_bindgen_test]
+fn search() {
+    let mut game = Game::from_moves("f5d6").unwrap();
+    let output = game.search(&Limits::depth(4));
+    assert_eq!(output.depth(), 4);
+    assert!(game.legal_moves().contains(&output.best_move()));
+    assert!(output.pv().starts_with(&output.best_move()));
+
+    let output = game.search(&Limits::time(50));
+    game.play(&output.best_move()).unwrap();
+}
+
+#[wasm_bindgen_test]
+fn svg() {
+    let svg = Game::new().svg();
+    assert!(svg.ends_with("</svg>"));
+    assert_eq!(svg.matches("<circle").count(), 4);
+}